@@ -0,0 +1,89 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Abstract destinations for decoded audio samples.
+
+use std::collections::VecDeque;
+
+/// Something that can play decoded audio, such as a sound card.
+pub trait AudioSink {
+    /// Queues up samples for playback. `samples` is in planar format, with one entry per channel.
+    fn enqueue(&mut self, samples: &[&[f32]]) -> Result<(),()>;
+
+    /// Pauses or resumes playback. While paused, the sink must emit silence without consuming any
+    /// queued samples, so that playback picks up exactly where it left off when resumed.
+    fn set_paused(&mut self, paused: bool);
+}
+
+/// A queue of interleaved samples waiting to be handed to the audio hardware, suitable for use
+/// from an audio callback. Pausing the buffer makes it produce silence while retaining its
+/// contents.
+pub struct AudioRingBuffer {
+    /// The queued samples, in interleaved format.
+    samples: VecDeque<f32>,
+    /// Whether playback is paused.
+    paused: bool,
+}
+
+impl Default for AudioRingBuffer {
+    fn default() -> AudioRingBuffer {
+        AudioRingBuffer::new()
+    }
+}
+
+impl AudioRingBuffer {
+    pub fn new() -> AudioRingBuffer {
+        AudioRingBuffer {
+            samples: VecDeque::new(),
+            paused: false,
+        }
+    }
+
+    /// Returns the number of queued samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused
+    }
+
+    /// Appends interleaved samples to the end of the queue.
+    pub fn push(&mut self, samples: &[f32]) {
+        self.samples.extend(samples.iter().cloned())
+    }
+
+    /// Fills `output` with samples from the front of the queue. If the buffer is paused, `output`
+    /// is filled with silence and the queue is left untouched; if the queue runs dry, the rest of
+    /// `output` is filled with silence. Returns the number of samples consumed from the queue.
+    pub fn pop(&mut self, output: &mut [f32]) -> usize {
+        let mut consumed = 0;
+        if !self.paused {
+            for value in output.iter_mut() {
+                match self.samples.pop_front() {
+                    Some(sample) => *value = sample,
+                    None => break,
+                }
+                consumed += 1;
+            }
+        }
+        for value in output[consumed..].iter_mut() {
+            *value = 0.0
+        }
+        consumed
+    }
+}
@@ -9,6 +9,24 @@
 
 use videodecoder::VideoHeaders;
 
+pub const PROFILE_BASELINE: u32 = 66;
+pub const PROFILE_MAIN: u32 = 77;
+pub const PROFILE_EXTENDED: u32 = 88;
+pub const PROFILE_HIGH: u32 = 100;
+pub const PROFILE_HIGH_10: u32 = 110;
+pub const PROFILE_HIGH_422: u32 = 122;
+pub const PROFILE_HIGH_444: u32 = 244;
+
+/// Returns the profile and level of a stream, as recorded in its first sequence parameter set.
+pub fn profile_and_level(headers: &VideoHeaders) -> Option<(u32, u32)> {
+    match headers.h264_seq_headers() {
+        Some(ref seq_headers) if !seq_headers.is_empty() && seq_headers[0].len() >= 4 => {
+            Some((seq_headers[0][1] as u32, seq_headers[0][3] as u32))
+        }
+        _ => None,
+    }
+}
+
 /// Constructs an AVCC chunk from a set of decoder headers.
 pub fn create_avcc_chunk(headers: &VideoHeaders) -> Vec<u8> {
     let seq_headers = headers.h264_seq_headers().unwrap();
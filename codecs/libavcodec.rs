@@ -11,6 +11,7 @@
 
 use audiodecoder;
 use codecs::h264;
+use codecs::theora;
 use pixelformat::PixelFormat;
 use timing::Timestamp;
 use videodecoder;
@@ -27,6 +28,7 @@ use std::slice;
 pub type AvCodecId = ffi::AVCodecID;
 
 pub const AV_CODEC_ID_H264: AvCodecId = 28;
+pub const AV_CODEC_ID_THEORA: AvCodecId = 31;
 pub const AV_CODEC_ID_AAC: AvCodecId = 0x15000 + 2;
 
 pub const FF_INPUT_BUFFER_PADDING_SIZE: usize = 32;
@@ -453,6 +455,24 @@ struct VideoDecoderImpl {
 }
 
 impl VideoDecoderImpl {
+    /// Frames are handed out as 8-bit 4:2:0, which rules out High 10 and the profiles above it.
+    fn supports_h264(profile: Option<u32>, _: Option<u32>, _: i32, _: i32) -> bool {
+        let profile_supported = match profile {
+            None => true,
+            Some(profile) => {
+                profile == h264::PROFILE_BASELINE || profile == h264::PROFILE_MAIN ||
+                    profile == h264::PROFILE_EXTENDED || profile == h264::PROFILE_HIGH
+            }
+        };
+        init();
+        profile_supported && AvCodec::find_decoder(AV_CODEC_ID_H264).is_ok()
+    }
+
+    fn supports_theora(_: Option<u32>, _: Option<u32>, _: i32, _: i32) -> bool {
+        init();
+        AvCodec::find_decoder(AV_CODEC_ID_THEORA).is_ok()
+    }
+
     fn h264(headers: &videodecoder::VideoHeaders, _: i32, _: i32)
            -> Result<Box<videodecoder::VideoDecoder + 'static>,()> {
         init();
@@ -468,6 +488,22 @@ impl VideoDecoderImpl {
             context: context,
         }) as Box<videodecoder::VideoDecoder + 'static>)
     }
+
+    fn theora(headers: &videodecoder::VideoHeaders, _: i32, _: i32)
+              -> Result<Box<videodecoder::VideoDecoder + 'static>,()> {
+        init();
+
+        let extra_data = theora::create_extra_data(headers);
+        let codec = try!(AvCodec::find_decoder(AV_CODEC_ID_THEORA));
+        let mut context = AvCodecContext::new(&codec);
+        context.set_extra_data(extra_data);
+        let (result, _) = context.open(&codec, AvDictionary::new());
+        try!(result);
+        Ok(Box::new(VideoDecoderImpl {
+            codec: codec,
+            context: context,
+        }) as Box<videodecoder::VideoDecoder + 'static>)
+    }
 }
 
 impl videodecoder::VideoDecoder for VideoDecoderImpl {
@@ -542,6 +578,14 @@ pub const VIDEO_DECODER: videodecoder::RegisteredVideoDecoder =
     videodecoder::RegisteredVideoDecoder {
         id: [ b'a', b'v', b'c', b' ' ],
         constructor: VideoDecoderImpl::h264,
+        supports: VideoDecoderImpl::supports_h264,
+    };
+
+pub const THEORA_VIDEO_DECODER: videodecoder::RegisteredVideoDecoder =
+    videodecoder::RegisteredVideoDecoder {
+        id: [ b't', b'h', b'e', b'o' ],
+        constructor: VideoDecoderImpl::theora,
+        supports: VideoDecoderImpl::supports_theora,
     };
 
 // Implementation of the abstract `AudioDecoder` interface
@@ -0,0 +1,32 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use videodecoder::VideoHeaders;
+
+/// The identification, comment, and setup header packets of a Theora stream, in that order.
+pub struct TheoraHeaders {
+    pub headers: Vec<Vec<u8>>,
+}
+
+impl VideoHeaders for TheoraHeaders {
+    fn theora_headers<'a>(&'a self) -> Option<Vec<&'a [u8]>> {
+        Some(self.headers.iter().map(|header| &header[..]).collect())
+    }
+}
+
+/// Constructs codec extra data from a set of decoder headers, in the form FFmpeg expects: each
+/// header packet prefixed with its length as a 16-bit big-endian integer.
+pub fn create_extra_data(headers: &VideoHeaders) -> Vec<u8> {
+    let mut extra_data = Vec::new();
+    for header in headers.theora_headers().unwrap().iter() {
+        extra_data.extend_from_slice(&[ (header.len() >> 8) as u8, header.len() as u8 ]);
+        extra_data.extend_from_slice(&header);
+    }
+    extra_data
+}
@@ -178,6 +178,14 @@ struct VideoDecoderImpl {
 }
 
 impl VideoDecoderImpl {
+    /// VP8 defines profiles 0 through 3, all of which `libvpx` decodes.
+    fn supports(profile: Option<u32>, _: Option<u32>, _: i32, _: i32) -> bool {
+        match profile {
+            None => true,
+            Some(profile) => profile <= 3,
+        }
+    }
+
     fn new(_: &videodecoder::VideoHeaders, _: i32, _: i32)
            -> Result<Box<videodecoder::VideoDecoder + 'static>,()> {
         match VpxCodec::init(&VpxCodecIface::vp8()) {
@@ -258,6 +266,7 @@ pub const VIDEO_DECODER: videodecoder::RegisteredVideoDecoder =
     videodecoder::RegisteredVideoDecoder {
         id: [ b'V', b'P', b'8', b'0' ],
         constructor: VideoDecoderImpl::new,
+        supports: VideoDecoderImpl::supports,
     };
 
 #[allow(non_camel_case_types)]
@@ -11,6 +11,7 @@ use audiodecoder;
 use containers::gif;
 use containers::mkv;
 use containers::mp4;
+use containers::ogg;
 use pixelformat::PixelFormat;
 use streaming::StreamReader;
 use timing::Timestamp;
@@ -40,12 +41,28 @@ pub trait Track {
 }
 
 pub trait VideoTrack : Track {
-    /// Returns the width of this track in pixels.
+    /// Returns the declared (display) width of this track in pixels, as recorded in the container.
+    /// This may differ from the coded width that decoded frames report; see
+    /// `DecodedVideoFrame::width()`.
     fn width(&self) -> u16;
 
-    /// Returns the height of this track in pixels.
+    /// Returns the declared (display) height of this track in pixels, as recorded in the
+    /// container. This may differ from the coded height that decoded frames report; see
+    /// `DecodedVideoFrame::height()`.
     fn height(&self) -> u16;
 
+    /// Returns the coded width of this track in pixels, as recorded in the codec configuration.
+    /// This is what decoders need to be initialized with. By default, this is the declared width.
+    fn coded_width(&self) -> u16 {
+        self.width()
+    }
+
+    /// Returns the coded height of this track in pixels, as recorded in the codec configuration.
+    /// This is what decoders need to be initialized with. By default, this is the declared height.
+    fn coded_height(&self) -> u16 {
+        self.height()
+    }
+
     /// Returns the frame rate of this track in Hertz.
     fn frame_rate(&self) -> c_double;
 
@@ -153,9 +170,10 @@ impl RegisteredContainerReader {
     }
 }
 
-pub static CONTAINER_READERS: [RegisteredContainerReader; 3] = [
+pub static CONTAINER_READERS: [RegisteredContainerReader; 4] = [
     mkv::CONTAINER_READER,
     mp4::CONTAINER_READER,
     gif::CONTAINER_READER,
+    ogg::CONTAINER_READER,
 ];
 
@@ -373,7 +373,8 @@ impl<'a> ColorMapObject<'a> {
 }
 
 pub enum ExtensionBlock<'a> {
-    Continue,
+    /// A further data sub-block belonging to the extension before it.
+    Continue(&'a [u8]),
     Comment(&'a [u8]),
     Graphics(GraphicsControlBlock),
     Plaintext(&'a [u8]),
@@ -384,7 +385,7 @@ pub enum ExtensionBlock<'a> {
 impl<'a> ExtensionBlock<'a> {
     unsafe fn from_ptr(block: *mut ffi::ExtensionBlock) -> ExtensionBlock<'a> {
         return match (*block).Function {
-            ffi::CONTINUE_EXT_FUNC_CODE => ExtensionBlock::Continue,
+            ffi::CONTINUE_EXT_FUNC_CODE => ExtensionBlock::Continue(to_byte_slice(block)),
             ffi::COMMENT_EXT_FUNC_CODE => ExtensionBlock::Comment(to_byte_slice(block)),
             ffi::GRAPHICS_EXT_FUNC_CODE => {
                 let mut graphics_control_block = ffi::GraphicsControlBlock {
@@ -440,7 +441,7 @@ impl GraphicsControlBlock {
 }
 
 #[repr(i32)]
-#[derive(Copy, Clone, FromPrimitive)]
+#[derive(Copy, Clone, Debug, PartialEq, FromPrimitive)]
 pub enum DisposalMode {
     Unspecified = 0,
     DoNot = 1,
@@ -715,6 +716,10 @@ struct VideoDecoderImpl {
 }
 
 impl VideoDecoderImpl {
+    fn supports(_: Option<u32>, _: Option<u32>, _: i32, _: i32) -> bool {
+        true
+    }
+
     fn new(_: &videodecoder::VideoHeaders, width: i32, height: i32)
            -> Result<Box<videodecoder::VideoDecoder + 'static>,()> {
         Ok(Box::new(VideoDecoderImpl {
@@ -812,8 +817,452 @@ pub const VIDEO_DECODER: videodecoder::RegisteredVideoDecoder =
     videodecoder::RegisteredVideoDecoder {
         id: [ b'G', b'I', b'F', b'f' ],
         constructor: VideoDecoderImpl::new,
+        supports: VideoDecoderImpl::supports,
     };
 
+// Pure-Rust encoder
+
+/// Writes animated GIFs from RGBA frames. Each frame is reduced to its own 256-color palette by
+/// median cut and LZW-compressed. Pixels with an alpha below 128 become transparent.
+#[cfg(feature="gif-encoder")]
+pub mod encoder {
+    use containers::gif::DisposalMode;
+    use pixelformat::RgbColor;
+
+    use std::collections::HashMap;
+    use std::io::Write;
+    use std::u16;
+    use time::Duration;
+
+    /// The largest number of LZW codes a GIF decoder can track.
+    const MAX_CODE: u16 = 4095;
+
+    /// Alpha values below this are treated as fully transparent.
+    const ALPHA_THRESHOLD: u8 = 128;
+
+    /// Writes an animated GIF89a file to a stream, one frame at a time.
+    pub struct GifWriter<W> {
+        /// The underlying stream.
+        writer: W,
+        /// The width of every frame, in pixels.
+        width: u16,
+        /// The height of every frame, in pixels.
+        height: u16,
+    }
+
+    impl<W> GifWriter<W> where W: Write {
+        /// Writes the GIF header for an animation of the given size. `loop_count` is the number of
+        /// times to repeat the animation, recorded in a NETSCAPE2.0 extension; zero means forever.
+        pub fn new(mut writer: W, width: u16, height: u16, loop_count: u16)
+                   -> Result<GifWriter<W>,()> {
+            // Header and logical screen descriptor. Every frame has a local color table, so there
+            // is no global one.
+            let mut header = Vec::new();
+            header.extend_from_slice(b"GIF89a");
+            push_u16(&mut header, width);
+            push_u16(&mut header, height);
+            header.extend_from_slice(&[0b0111_0000, 0, 0]);
+
+            // NETSCAPE2.0 looping extension.
+            header.extend_from_slice(&[0x21, 0xff, 0x0b]);
+            header.extend_from_slice(b"NETSCAPE2.0");
+            header.extend_from_slice(&[0x03, 0x01]);
+            push_u16(&mut header, loop_count);
+            header.push(0x00);
+
+            if writer.write_all(&header).is_err() {
+                return Err(())
+            }
+            Ok(GifWriter {
+                writer: writer,
+                width: width,
+                height: height,
+            })
+        }
+
+        /// Appends a frame. `rgba` must contain `width * height` tightly packed 32-bit RGBA
+        /// pixels. `delay` is how long the frame is shown for, rounded to the nearest hundredth of
+        /// a second.
+        pub fn write_frame(&mut self, rgba: &[u8], delay: Duration) -> Result<(),()> {
+            if rgba.len() != self.width as usize * self.height as usize * 4 {
+                return Err(())
+            }
+
+            let image = quantize(rgba);
+            let mut table_bits = 1;
+            while (1 << table_bits) < image.palette.len() {
+                table_bits += 1
+            }
+
+            // Graphics control extension. The disposal mode says what happens to a frame once
+            // it's been shown, so it has to be set on the frame *before* one with transparent
+            // pixels to keep it from showing through them. Every frame covers the whole canvas, so
+            // clearing each one to the background is always safe.
+            let (transparent_flag, transparent_index) = match image.transparent_index {
+                Some(index) => (1, index),
+                None => (0, 0),
+            };
+            let disposal_mode = DisposalMode::Background;
+            let mut block = Vec::new();
+            block.extend_from_slice(&[0x21, 0xf9, 0x04]);
+            block.push(((disposal_mode as u8) << 2) | transparent_flag);
+            push_u16(&mut block, delay_to_centiseconds(delay));
+            block.extend_from_slice(&[transparent_index, 0x00]);
+
+            // Image descriptor, covering the whole logical screen.
+            block.extend_from_slice(&[0x2c, 0, 0, 0, 0]);
+            push_u16(&mut block, self.width);
+            push_u16(&mut block, self.height);
+            block.push(0x80 | (table_bits - 1) as u8);
+
+            // Local color table, padded out to a power of two.
+            for index in 0..(1 << table_bits) {
+                match image.palette.get(index) {
+                    Some(color) => block.extend_from_slice(&[color.r, color.g, color.b]),
+                    None => block.extend_from_slice(&[0, 0, 0]),
+                }
+            }
+
+            // Image data, as LZW codes packed into sub-blocks of at most 255 bytes.
+            let min_code_size = if table_bits < 2 {
+                2
+            } else {
+                table_bits as u8
+            };
+            block.push(min_code_size);
+            for sub_block in lzw_encode(&image.indices, min_code_size).chunks(255) {
+                block.push(sub_block.len() as u8);
+                block.extend_from_slice(sub_block);
+            }
+            block.push(0x00);
+
+            match self.writer.write_all(&block) {
+                Ok(_) => Ok(()),
+                Err(_) => Err(()),
+            }
+        }
+
+        /// Writes the GIF trailer and returns the underlying stream.
+        pub fn finish(mut self) -> Result<W,()> {
+            if self.writer.write_all(&[0x3b]).is_err() || self.writer.flush().is_err() {
+                return Err(())
+            }
+            Ok(self.writer)
+        }
+    }
+
+    fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+        bytes.extend_from_slice(&[value as u8, (value >> 8) as u8])
+    }
+
+    fn delay_to_centiseconds(delay: Duration) -> u16 {
+        let centiseconds = (delay.num_milliseconds() + 5) / 10;
+        if centiseconds <= 0 {
+            0
+        } else if centiseconds >= u16::MAX as i64 {
+            u16::MAX
+        } else {
+            centiseconds as u16
+        }
+    }
+
+    /// An RGBA image reduced to at most 256 colors.
+    pub struct QuantizedImage {
+        /// The colors in the image. The transparent color, if any, is included but meaningless.
+        pub palette: Vec<RgbColor>,
+        /// One palette index per pixel.
+        pub indices: Vec<u8>,
+        /// The palette index used for transparent pixels, if there are any.
+        pub transparent_index: Option<u8>,
+    }
+
+    /// A single distinct color in an image, along with the number of pixels that have it.
+    #[derive(Clone, Copy)]
+    struct HistogramEntry {
+        color: [u8; 3],
+        count: u32,
+    }
+
+    /// A range of histogram entries that will be represented by one palette color.
+    #[derive(Clone, Copy)]
+    struct ColorBox {
+        start: usize,
+        end: usize,
+    }
+
+    impl ColorBox {
+        /// Returns the color channel with the largest spread in this box, and that spread.
+        fn longest_axis(&self, entries: &[HistogramEntry]) -> (usize, u8) {
+            let (mut min, mut max) = ([255, 255, 255], [0, 0, 0]);
+            for entry in entries[self.start..self.end].iter() {
+                for channel in 0..3 {
+                    if entry.color[channel] < min[channel] {
+                        min[channel] = entry.color[channel]
+                    }
+                    if entry.color[channel] > max[channel] {
+                        max[channel] = entry.color[channel]
+                    }
+                }
+            }
+            let mut axis = (0, 0);
+            for channel in 0..3 {
+                if max[channel] >= min[channel] && max[channel] - min[channel] >= axis.1 {
+                    axis = (channel, max[channel] - min[channel])
+                }
+            }
+            axis
+        }
+
+        /// Returns the average color of the pixels in this box.
+        fn average(&self, entries: &[HistogramEntry]) -> RgbColor {
+            let (mut sums, mut count) = ([0u64, 0, 0], 0u64);
+            for entry in entries[self.start..self.end].iter() {
+                for channel in 0..3 {
+                    sums[channel] += entry.color[channel] as u64 * entry.count as u64
+                }
+                count += entry.count as u64
+            }
+            RgbColor {
+                r: ((sums[0] + count / 2) / count) as u8,
+                g: ((sums[1] + count / 2) / count) as u8,
+                b: ((sums[2] + count / 2) / count) as u8,
+            }
+        }
+    }
+
+    /// Reduces an RGBA image to at most 256 colors (including one for transparency, if needed) by
+    /// median cut.
+    pub fn quantize(rgba: &[u8]) -> QuantizedImage {
+        // Build a histogram of the opaque colors.
+        let mut counts = HashMap::new();
+        let mut has_transparency = false;
+        for pixel in rgba.chunks(4) {
+            if pixel[3] < ALPHA_THRESHOLD {
+                has_transparency = true;
+                continue
+            }
+            *counts.entry([pixel[0], pixel[1], pixel[2]]).or_insert(0) += 1
+        }
+        let mut entries: Vec<HistogramEntry> = counts.into_iter().map(|(color, count)| {
+            HistogramEntry {
+                color: color,
+                count: count,
+            }
+        }).collect();
+
+        // Repeatedly split the box with the widest range of colors at its median, along its
+        // longest axis.
+        let max_colors = if has_transparency {
+            255
+        } else {
+            256
+        };
+        let mut boxes = Vec::new();
+        if !entries.is_empty() {
+            boxes.push(ColorBox {
+                start: 0,
+                end: entries.len(),
+            })
+        }
+        while boxes.len() < max_colors {
+            let mut best: Option<(usize, usize, u8)> = None;
+            for (box_index, color_box) in boxes.iter().enumerate() {
+                if color_box.end - color_box.start < 2 {
+                    continue
+                }
+                let (axis, range) = color_box.longest_axis(&entries);
+                match best {
+                    Some((_, _, best_range)) if best_range >= range => {}
+                    _ => best = Some((box_index, axis, range)),
+                }
+            }
+            let (box_index, axis) = match best {
+                None => break,
+                Some((box_index, axis, _)) => (box_index, axis),
+            };
+
+            let color_box = boxes[box_index];
+            let slice = &mut entries[color_box.start..color_box.end];
+            slice.sort_by_key(|entry| entry.color[axis]);
+            let total: u64 = slice.iter().map(|entry| entry.count as u64).sum();
+            let (mut running_total, mut split) = (0, 1);
+            for (index, entry) in slice.iter().enumerate() {
+                running_total += entry.count as u64;
+                if running_total * 2 >= total {
+                    split = index + 1;
+                    break
+                }
+            }
+            if split >= slice.len() {
+                split = slice.len() - 1
+            }
+
+            boxes[box_index].end = color_box.start + split;
+            boxes.push(ColorBox {
+                start: color_box.start + split,
+                end: color_box.end,
+            })
+        }
+
+        // Assign each box a palette entry, and map every color to the box that contains it.
+        let mut palette = Vec::new();
+        let mut color_to_index = HashMap::new();
+        for color_box in boxes.iter() {
+            let index = palette.len() as u8;
+            palette.push(color_box.average(&entries));
+            for entry in entries[color_box.start..color_box.end].iter() {
+                color_to_index.insert(entry.color, index);
+            }
+        }
+        let transparent_index = if has_transparency {
+            palette.push(RgbColor {
+                r: 0,
+                g: 0,
+                b: 0,
+            });
+            Some((palette.len() - 1) as u8)
+        } else {
+            None
+        };
+
+        let indices = rgba.chunks(4).map(|pixel| {
+            if pixel[3] < ALPHA_THRESHOLD {
+                transparent_index.unwrap()
+            } else {
+                color_to_index[&[pixel[0], pixel[1], pixel[2]]]
+            }
+        }).collect();
+
+        QuantizedImage {
+            palette: palette,
+            indices: indices,
+            transparent_index: transparent_index,
+        }
+    }
+
+    /// Packs variable-width codes into bytes, least significant bit first.
+    struct BitWriter {
+        bytes: Vec<u8>,
+        accumulator: u32,
+        bit_count: u8,
+    }
+
+    impl BitWriter {
+        fn new() -> BitWriter {
+            BitWriter {
+                bytes: Vec::new(),
+                accumulator: 0,
+                bit_count: 0,
+            }
+        }
+
+        fn write(&mut self, code: u16, width: u8) {
+            self.accumulator |= (code as u32) << self.bit_count;
+            self.bit_count += width;
+            while self.bit_count >= 8 {
+                self.bytes.push(self.accumulator as u8);
+                self.accumulator >>= 8;
+                self.bit_count -= 8;
+            }
+        }
+
+        fn finish(mut self) -> Vec<u8> {
+            if self.bit_count > 0 {
+                self.bytes.push(self.accumulator as u8)
+            }
+            self.bytes
+        }
+    }
+
+    /// The state of the LZW code table, shared between encoding steps.
+    struct LzwState {
+        /// Maps a (prefix code, next index) pair to its code.
+        table: HashMap<(u16, u8), u16>,
+        /// The minimum code size, as written to the stream.
+        min_code_size: u8,
+        /// The current code width, in bits.
+        width: u8,
+        /// The most recently assigned code.
+        last_code: u16,
+    }
+
+    impl LzwState {
+        fn clear_code(&self) -> u16 {
+            1 << self.min_code_size
+        }
+
+        fn end_code(&self) -> u16 {
+            self.clear_code() + 1
+        }
+
+        fn reset(&mut self) {
+            self.table.clear();
+            self.width = self.min_code_size + 1;
+            self.last_code = self.end_code();
+        }
+
+        /// Reserves the next code, widening the codes that follow as the decoder will. If the
+        /// table is full, emits a clear code and returns `None` instead.
+        fn next_code(&mut self, output: &mut BitWriter) -> Option<u16> {
+            self.last_code += 1;
+            if self.last_code == 1 << self.width {
+                self.width += 1
+            }
+            if self.last_code == MAX_CODE {
+                let clear_code = self.clear_code();
+                output.write(clear_code, self.width);
+                self.reset();
+                return None
+            }
+            Some(self.last_code)
+        }
+    }
+
+    /// Compresses a sequence of palette indices with the variant of LZW that GIF uses. The output
+    /// still needs to be split into sub-blocks.
+    pub fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+        let mut output = BitWriter::new();
+        let mut state = LzwState {
+            table: HashMap::new(),
+            min_code_size: min_code_size,
+            width: 0,
+            last_code: 0,
+        };
+        state.reset();
+        let clear_code = state.clear_code();
+        output.write(clear_code, state.width);
+
+        let mut indices = indices.iter();
+        let mut prefix = match indices.next() {
+            Some(&index) => index as u16,
+            None => {
+                let end_code = state.end_code();
+                output.write(end_code, state.width);
+                return output.finish()
+            }
+        };
+        for &index in indices {
+            if let Some(&code) = state.table.get(&(prefix, index)) {
+                prefix = code;
+                continue
+            }
+            output.write(prefix, state.width);
+            if let Some(code) = state.next_code(&mut output) {
+                state.table.insert((prefix, index), code);
+            }
+            prefix = index as u16;
+        }
+
+        // The decoder adds one more table entry after reading the final code, so account for it
+        // before writing the end code.
+        output.write(prefix, state.width);
+        state.next_code(&mut output);
+        let end_code = state.end_code();
+        output.write(end_code, state.width);
+        output.finish()
+    }
+}
+
 pub mod ffi {
     use libc::{c_char, c_int, c_uchar, c_uint, c_void, size_t};
 
@@ -15,13 +15,14 @@ use streaming::StreamReader;
 use timing::Timestamp;
 use videodecoder;
 
-use libc::{self, c_char, c_double, c_int, c_long, c_void};
+use libc::{self, c_char, c_double, c_float, c_int, c_long, c_void};
 use std::ffi::{CString, CStr};
 use std::mem;
 use std::io::SeekFrom;
 use std::ptr;
 use std::slice;
 use std::str::{self, FromStr};
+use std::u16;
 
 pub struct Mp4FileHandle {
     handle: ffi::MP4FileHandle,
@@ -120,6 +121,18 @@ impl Mp4FileHandle {
         }
     }
 
+    /// Returns the display width of the given track from its `tkhd` atom, rounded to the nearest
+    /// pixel. This is stored as 16.16 fixed point, unlike the width in the sample description.
+    pub fn display_width(&self, track_id: ffi::MP4TrackId) -> Result<u16,()> {
+        self.float_property(track_id, b"tkhd.width").map(fixed_point_dimension_to_pixels)
+    }
+
+    /// Returns the display height of the given track from its `tkhd` atom, rounded to the nearest
+    /// pixel. This is stored as 16.16 fixed point, unlike the height in the sample description.
+    pub fn display_height(&self, track_id: ffi::MP4TrackId) -> Result<u16,()> {
+        self.float_property(track_id, b"tkhd.height").map(fixed_point_dimension_to_pixels)
+    }
+
     pub fn frame_rate(&self, track_id: ffi::MP4TrackId) -> c_double {
         unsafe {
             ffi::MP4GetTrackVideoFrameRate(self.handle, track_id)
@@ -161,6 +174,23 @@ impl Mp4FileHandle {
         }
     }
 
+    pub fn float_property(&self, track_id: ffi::MP4TrackId, property_name: &[u8])
+                          -> Result<c_float,()> {
+        let property_name = CString::new(property_name).unwrap();
+        let mut value = 0.0;
+        unsafe {
+            let ok = ffi::MP4GetTrackFloatProperty(self.handle,
+                                                   track_id,
+                                                   property_name.as_ptr(),
+                                                   &mut value);
+            if ok {
+                Ok(value)
+            } else {
+                Err(())
+            }
+        }
+    }
+
     pub fn bytes_property<'a>(&'a self, track_id: ffi::MP4TrackId, property_name: &[u8])
                               -> Result<&'a [u8],()> {
         let property_name = CString::new(property_name).unwrap();
@@ -534,10 +564,24 @@ impl<'a> container::Track for VideoTrackImpl<'a> {
 
 impl<'a> container::VideoTrack for VideoTrackImpl<'a> {
     fn width(&self) -> u16 {
-        self.handle.width(self.id)
+        match self.handle.display_width(self.id) {
+            Ok(width) if width != 0 => width,
+            _ => self.handle.width(self.id),
+        }
     }
 
     fn height(&self) -> u16 {
+        match self.handle.display_height(self.id) {
+            Ok(height) if height != 0 => height,
+            _ => self.handle.height(self.id),
+        }
+    }
+
+    fn coded_width(&self) -> u16 {
+        self.handle.width(self.id)
+    }
+
+    fn coded_height(&self) -> u16 {
         self.handle.height(self.id)
     }
 
@@ -687,6 +731,18 @@ impl videodecoder::VideoHeaders for VideoHeadersH264Impl {
 	}
 }
 
+/// `mp4v2` hands 16.16 fixed-point dimensions back to us as floats; round them to whole pixels,
+/// clamping to the range of a `u16`.
+pub fn fixed_point_dimension_to_pixels(dimension: c_float) -> u16 {
+    if dimension <= 0.0 {
+        0
+    } else if dimension >= u16::MAX as c_float {
+        u16::MAX
+    } else {
+        dimension.round() as u16
+    }
+}
+
 fn get_codec(handle: &Mp4FileHandle, id: ffi::MP4TrackId) -> Option<Vec<u8>> {
     static TABLE: [(&'static [u8], [u8; 4]); 3] = [
         (b"avc1", [b'a', b'v', b'c', b' ']),
@@ -717,7 +773,7 @@ pub const CONTAINER_READER: container::RegisteredContainerReader =
 #[allow(missing_copy_implementations)]
 #[allow(non_snake_case)]
 pub mod ffi {
-    use libc::{c_char, c_double, c_int, c_void};
+    use libc::{c_char, c_double, c_float, c_int, c_void};
 
     pub enum MP4FileHandleStruct {}
     #[repr(C)]
@@ -801,6 +857,11 @@ pub mod ffi {
                                           propName: *const c_char,
                                           retvalue: *mut u64)
                                           -> bool;
+        pub fn MP4GetTrackFloatProperty(hFile: MP4FileHandle,
+                                        trackId: MP4TrackId,
+                                        propName: *const c_char,
+                                        retvalue: *mut c_float)
+                                        -> bool;
         pub fn MP4GetTrackBytesProperty(hFile: MP4FileHandle,
                                         trackId: MP4TrackId,
                                         propName: *const c_char,
@@ -7,16 +7,32 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-//! Basic support for Ogg, Vorbis audio only at present.
-//!
-//! TODO(pcwalton): Support video and other codecs.
+//! Support for Ogg, with Vorbis audio and Theora video.
 
-use libc::{c_char, c_int, c_long};
+use audiodecoder;
+use codecs::theora::TheoraHeaders;
+use codecs::vorbis::VorbisHeaders;
+use container;
+use pixelformat::PixelFormat;
+use streaming::StreamReader;
+use timing::Timestamp;
+use videodecoder;
+
+use libc::{c_char, c_double, c_int, c_long};
+use std::cell::RefCell;
 use std::i32;
+use std::io::Read;
 use std::marker::PhantomData;
 use std::mem;
 use std::slice;
 
+/// The number of bytes to read from the underlying stream at a time.
+const READ_SIZE: usize = 4096;
+
+/// The value of the pixel format field of a Theora identification header for 4:2:0 chroma
+/// subsampling.
+const THEORA_PIXEL_FORMAT_420: u8 = 0;
+
 pub struct SyncState {
     state: ffi::ogg_sync_state,
 }
@@ -62,7 +78,7 @@ impl SyncState {
             page = mem::uninitialized();
             ffi::ogg_sync_pageout(&mut self.state, &mut page)
         };
-        if result == 0 {
+        if result == 1 {
             Ok(Page {
                 page: page,
             })
@@ -83,11 +99,23 @@ impl Page {
         }
     }
 
+    pub fn bos(&self) -> bool {
+        unsafe {
+            ffi::ogg_page_bos(&self.page) != 0
+        }
+    }
+
     pub fn eos(&self) -> bool {
         unsafe {
             ffi::ogg_page_eos(&self.page) != 0
         }
     }
+
+    pub fn granulepos(&self) -> i64 {
+        unsafe {
+            ffi::ogg_page_granulepos(&self.page)
+        }
+    }
 }
 
 pub struct StreamState {
@@ -120,15 +148,21 @@ impl StreamState {
         }
     }
 
-    pub fn packetout(&mut self) -> Packet {
+    /// Returns the next packet in this stream. The error value is 0 if more pages are needed and
+    /// -1 if there was a gap in the data.
+    pub fn packetout<'a>(&'a mut self) -> Result<Packet<'a>,c_int> {
         let mut packet;
-        unsafe {
+        let result = unsafe {
             packet = mem::uninitialized();
-            assert!(ffi::ogg_stream_packetout(&mut self.state, &mut packet) == 0)
-        }
-        Packet {
-            packet: packet,
-            marker: PhantomData,
+            ffi::ogg_stream_packetout(&mut self.state, &mut packet)
+        };
+        if result == 1 {
+            Ok(Packet {
+                packet: packet,
+                marker: PhantomData,
+            })
+        } else {
+            Err(result)
         }
     }
 }
@@ -163,8 +197,655 @@ impl<'a> Packet<'a> {
     pub fn raw_packet<'b>(&'b mut self) -> &'b mut ffi::ogg_packet {
         &mut self.packet
     }
+
+    pub fn data<'b>(&'b self) -> &'b [u8] {
+        if self.packet.bytes == 0 {
+            return &[]
+        }
+        unsafe {
+            slice::from_raw_parts(self.packet.packet, self.packet.bytes as usize)
+        }
+    }
+}
+
+// Implementation of the abstract `ContainerReader` interface
+
+#[derive(Clone, Copy, PartialEq)]
+enum StreamCodec {
+    Theora,
+    Vorbis,
+    Unknown,
+}
+
+impl StreamCodec {
+    /// Identifies the codec of a logical stream from its first packet.
+    fn from_first_packet(packet: &[u8]) -> StreamCodec {
+        // Theora tracks are reported as 4:2:0, so other chroma subsamplings are left alone rather
+        // than shown in the wrong colors.
+        if packet.len() >= 42 && &packet[0..7] == b"\x80theora" &&
+                (packet[41] >> 3) & 3 == THEORA_PIXEL_FORMAT_420 {
+            StreamCodec::Theora
+        } else if packet.len() >= 30 && &packet[0..7] == b"\x01vorbis" {
+            StreamCodec::Vorbis
+        } else {
+            StreamCodec::Unknown
+        }
+    }
+
+    fn header_count(&self) -> usize {
+        match *self {
+            StreamCodec::Theora | StreamCodec::Vorbis => 3,
+            StreamCodec::Unknown => 0,
+        }
+    }
+}
+
+/// The block sizes of a Vorbis stream, which determine how many samples each packet decodes to.
+struct VorbisBlockSizes {
+    /// The short and long block sizes, in samples.
+    sizes: [u32; 2],
+    /// Whether each mode in the setup header uses long blocks.
+    mode_block_flags: Vec<bool>,
+}
+
+impl VorbisBlockSizes {
+    fn new(id: &[u8], setup: &[u8]) -> Option<VorbisBlockSizes> {
+        let (short_exponent, long_exponent) = (id[28] & 0xf, id[28] >> 4);
+        if short_exponent < 6 || long_exponent > 13 || short_exponent > long_exponent {
+            return None
+        }
+        let mode_block_flags = match read_vorbis_mode_block_flags(setup) {
+            Some(mode_block_flags) => mode_block_flags,
+            None => return None,
+        };
+        Some(VorbisBlockSizes {
+            sizes: [1 << short_exponent, 1 << long_exponent],
+            mode_block_flags: mode_block_flags,
+        })
+    }
+
+    /// Returns the block size of the given packet, or `None` if it isn't an audio packet.
+    fn block_size(&self, packet: &[u8]) -> Option<u32> {
+        if packet.is_empty() || (packet[0] & 1) != 0 {
+            return None
+        }
+        let mode_bits = 32 - (self.mode_block_flags.len() as u32 - 1).leading_zeros();
+        let mode = ((packet[0] >> 1) as usize) & ((1 << mode_bits) - 1);
+        self.mode_block_flags.get(mode).map(|&long| self.sizes[long as usize])
+    }
+}
+
+/// Reads the block flag of each mode at the end of a Vorbis setup header.
+///
+/// Finding the modes from the front would mean parsing all the codebooks, floors, residues, and
+/// mappings before them, so this works backwards from the framing bit instead, as FFmpeg and
+/// liboggz do. Each mode is 41 bits long, and the mode count comes just before the first one.
+fn read_vorbis_mode_block_flags(setup: &[u8]) -> Option<Vec<bool>> {
+    let mut reader = ReverseBitReader {
+        data: setup,
+        position: setup.len() * 8,
+    };
+
+    // Skip the padding after the framing bit.
+    while reader.position > 0 && reader.read(1) == 0 {}
+    let framing_bit_position = reader.position;
+
+    // Read back one mode at a time (block flag, window type, transform type, and mapping) until
+    // the fields stop making sense. The last count that matched the bits just before the modes is
+    // the mode count.
+    let (mut mode_count, mut matching_mode_count) = (0, 0);
+    while reader.position >= 97 {
+        if reader.read(8) > 63 || reader.read(16) != 0 || reader.read(16) != 0 {
+            break
+        }
+        reader.read(1);
+        mode_count += 1;
+        if mode_count > 64 {
+            break
+        }
+        let position = reader.position;
+        if reader.read(6) + 1 == mode_count {
+            matching_mode_count = mode_count
+        }
+        reader.position = position;
+    }
+    if matching_mode_count == 0 {
+        return None
+    }
+
+    reader.position = framing_bit_position;
+    let mut mode_block_flags = vec![false; matching_mode_count as usize];
+    for block_flag in mode_block_flags.iter_mut().rev() {
+        reader.read(40);
+        *block_flag = reader.read(1) != 0
+    }
+    Some(mode_block_flags)
+}
+
+/// Reads a Vorbis bitstream, which is packed least significant bit first, from back to front.
+struct ReverseBitReader<'a> {
+    data: &'a [u8],
+    /// The number of bits that have not been read yet.
+    position: usize,
+}
+
+impl<'a> ReverseBitReader<'a> {
+    /// Reads a field of the given width. Reading backwards, the most significant bit comes first.
+    fn read(&mut self, bit_count: usize) -> u32 {
+        let mut value = 0;
+        for _ in 0..bit_count {
+            self.position -= 1;
+            let bit = (self.data[self.position / 8] >> (self.position % 8)) & 1;
+            value = (value << 1) | (bit as u32)
+        }
+        value
+    }
+}
+
+struct StreamPacket {
+    data: Vec<u8>,
+    /// The time at which this packet starts, in the units of the stream's granule position.
+    ticks: i64,
+    /// The number of samples that this packet decodes to, for Vorbis.
+    duration: i64,
+}
+
+/// A logical bitstream within the physical Ogg stream, demultiplexed by serial number.
+struct LogicalStream {
+    state: StreamState,
+    serial_number: c_int,
+    codec: StreamCodec,
+    /// Whether the codec has been identified from the first packet yet.
+    identified: bool,
+    headers: Vec<Vec<u8>>,
+    vorbis_block_sizes: Option<VorbisBlockSizes>,
+    /// The block size of the last Vorbis audio packet.
+    last_block_size: Option<u32>,
+    packets: Vec<StreamPacket>,
+    /// The number of packets whose times are known. A Vorbis packet can only be timed once the
+    /// page that it ends on has been read.
+    timed_packet_count: usize,
+    /// The granule position at the end of the last timed packet for Vorbis, or at the end of the
+    /// last page for other codecs.
+    last_granule_position: i64,
+}
+
+impl LogicalStream {
+    fn new(serial_number: c_int) -> LogicalStream {
+        LogicalStream {
+            state: StreamState::new(serial_number),
+            serial_number: serial_number,
+            codec: StreamCodec::Unknown,
+            identified: false,
+            headers: Vec::new(),
+            vorbis_block_sizes: None,
+            last_block_size: None,
+            packets: Vec::new(),
+            timed_packet_count: 0,
+            last_granule_position: 0,
+        }
+    }
+
+    fn push_packet(&mut self, data: Vec<u8>) {
+        if !self.identified {
+            self.codec = StreamCodec::from_first_packet(&data);
+            self.identified = true
+        }
+        if self.headers.len() < self.codec.header_count() {
+            self.headers.push(data);
+            if self.codec == StreamCodec::Vorbis && self.headers.len() == 3 {
+                self.vorbis_block_sizes = VorbisBlockSizes::new(&self.headers[0],
+                                                                &self.headers[2])
+            }
+            return
+        }
+
+        // Nothing can decode the packets of streams in other codecs, so don't keep them around.
+        if self.codec == StreamCodec::Unknown {
+            return
+        }
+
+        let mut duration = 0;
+        if let Some(ref vorbis_block_sizes) = self.vorbis_block_sizes {
+            if let Some(block_size) = vorbis_block_sizes.block_size(&data) {
+                // Each packet's window overlaps half of the previous one's, and only the overlap
+                // is output, so the first packet decodes to no samples at all.
+                if let Some(last_block_size) = self.last_block_size {
+                    duration = (last_block_size / 4 + block_size / 4) as i64
+                }
+                self.last_block_size = Some(block_size)
+            }
+        }
+
+        let ticks = match self.codec {
+            // Every Theora packet is exactly one frame, so the frame number is just the packet
+            // index.
+            StreamCodec::Theora => self.packets.len() as i64,
+            StreamCodec::Vorbis | StreamCodec::Unknown => self.last_granule_position,
+        };
+        self.packets.push(StreamPacket {
+            data: data,
+            ticks: ticks,
+            duration: duration,
+        });
+        if self.codec != StreamCodec::Vorbis {
+            self.timed_packet_count = self.packets.len()
+        }
+    }
+
+    /// Called once every packet that ends on a page has been pushed.
+    fn finish_page(&mut self, granule_position: i64, end_of_stream: bool) {
+        if self.codec != StreamCodec::Vorbis {
+            if granule_position >= 0 {
+                self.last_granule_position = granule_position
+            }
+            return
+        }
+        if granule_position < 0 {
+            // No packet ended on this page.
+            return
+        }
+        if end_of_stream {
+            // The last granule position may cut the final packet short, so count forwards.
+            self.time_remaining_packets();
+            return
+        }
+
+        // The granule position is the end of the last packet on this page, so count backwards.
+        let mut ticks = granule_position;
+        for packet in self.packets[self.timed_packet_count..].iter_mut().rev() {
+            ticks -= packet.duration;
+            packet.ticks = ticks
+        }
+        self.timed_packet_count = self.packets.len();
+        self.last_granule_position = granule_position
+    }
+
+    /// Times any Vorbis packets left over by counting forwards from the last timed one.
+    fn time_remaining_packets(&mut self) {
+        let mut ticks = self.last_granule_position;
+        for packet in self.packets[self.timed_packet_count..].iter_mut() {
+            packet.ticks = ticks;
+            ticks += packet.duration
+        }
+        self.timed_packet_count = self.packets.len();
+        self.last_granule_position = ticks
+    }
+
+    fn track_type(&self) -> container::TrackType {
+        match self.codec {
+            StreamCodec::Theora => container::TrackType::Video,
+            StreamCodec::Vorbis => container::TrackType::Audio,
+            StreamCodec::Unknown => container::TrackType::Other,
+        }
+    }
+
+    /// Returns the frame rate of a Theora stream as a fraction.
+    fn theora_frame_rate(&self) -> (u32, u32) {
+        let id = &self.headers[0];
+        (read_u32_be(&id[22..26]), read_u32_be(&id[26..30]))
+    }
+
+    fn time(&self, packet_index: usize) -> Timestamp {
+        let ticks_per_second = match self.codec {
+            StreamCodec::Theora => {
+                let (numerator, denominator) = self.theora_frame_rate();
+                numerator as f64 / denominator as f64
+            }
+            StreamCodec::Vorbis => read_u32_le(&self.headers[0][12..16]) as f64,
+            StreamCodec::Unknown => 1.0,
+        };
+        Timestamp {
+            ticks: self.packets[packet_index].ticks,
+            ticks_per_second: ticks_per_second,
+        }
+    }
+}
+
+/// Splits the physical stream up into logical streams, reading a page at a time as packets are
+/// needed.
+///
+/// FIXME: Packets are kept once they've been read so that the player can go back to
+/// them, so memory use still grows with the length of the stream.
+struct Demuxer {
+    reader: Box<StreamReader>,
+    sync_state: SyncState,
+    streams: Vec<LogicalStream>,
+    /// Whether a page that doesn't begin a stream has been seen. Every logical stream must have
+    /// begun before then.
+    all_streams_begun: bool,
+    at_end: bool,
+}
+
+impl Demuxer {
+    /// Reads pages until the headers of every logical stream have been read.
+    fn new(reader: Box<StreamReader>) -> Result<Demuxer,()> {
+        let mut demuxer = Demuxer {
+            reader: reader,
+            sync_state: SyncState::new(),
+            streams: Vec::new(),
+            all_streams_begun: false,
+            at_end: false,
+        };
+        while !demuxer.all_streams_begun || demuxer.streams.iter().any(|stream| {
+            stream.headers.len() < stream.codec.header_count()
+        }) {
+            if !try!(demuxer.read_page()) {
+                break
+            }
+        }
+
+        // Drop streams whose headers were cut off.
+        demuxer.streams.retain(|stream| stream.headers.len() == stream.codec.header_count());
+        if demuxer.streams.is_empty() {
+            return Err(())
+        }
+        Ok(demuxer)
+    }
+
+    /// Reads the next page and passes its packets on to its logical stream. Returns false at the
+    /// end of the physical stream.
+    fn read_page(&mut self) -> Result<bool,()> {
+        let mut page;
+        loop {
+            match self.sync_state.pageout() {
+                Ok(next_page) => {
+                    page = next_page;
+                    break
+                }
+                Err(0) => {}
+                Err(_) => continue,
+            }
+
+            if self.at_end {
+                return Ok(false)
+            }
+            let bytes_read = match self.reader.read(self.sync_state.buffer(READ_SIZE as c_long)) {
+                Ok(bytes_read) => bytes_read,
+                Err(_) => return Err(()),
+            };
+            if bytes_read == 0 {
+                self.at_end = true;
+                for stream in self.streams.iter_mut() {
+                    stream.time_remaining_packets()
+                }
+                return Ok(false)
+            }
+            self.sync_state.wrote(bytes_read as c_long);
+        }
+
+        if !page.bos() {
+            self.all_streams_begun = true
+        }
+        let serial_number = page.serialno();
+        let index = match self.streams.iter().position(|stream| {
+            stream.serial_number == serial_number
+        }) {
+            Some(index) => index,
+            None if page.bos() && !self.all_streams_begun => {
+                self.streams.push(LogicalStream::new(serial_number));
+                self.streams.len() - 1
+            }
+            None => return Ok(true),
+        };
+
+        let stream = &mut self.streams[index];
+        stream.state.pagein(&mut page);
+        loop {
+            let data = match stream.state.packetout() {
+                Ok(packet) => packet.data().to_vec(),
+                Err(0) => break,
+                Err(_) => continue,
+            };
+            stream.push_packet(data)
+        }
+        stream.finish_page(page.granulepos(), page.eos());
+        Ok(true)
+    }
+
+    /// Reads pages until the given packet has been timed. Returns false if the stream ends first.
+    fn read_packet(&mut self, stream_index: usize, packet_index: usize) -> Result<bool,()> {
+        while self.streams[stream_index].timed_packet_count <= packet_index {
+            if !try!(self.read_page()) {
+                return Ok(false)
+            }
+        }
+        Ok(true)
+    }
+}
+
+struct ContainerReaderImpl {
+    demuxer: RefCell<Demuxer>,
+}
+
+impl ContainerReaderImpl {
+    fn new(reader: Box<StreamReader>) -> Result<Box<container::ContainerReader + 'static>,()> {
+        let demuxer = try!(Demuxer::new(reader));
+        Ok(Box::new(ContainerReaderImpl {
+            demuxer: RefCell::new(demuxer),
+        }) as Box<container::ContainerReader + 'static>)
+    }
+}
+
+impl container::ContainerReader for ContainerReaderImpl {
+    fn track_count(&self) -> u16 {
+        self.demuxer.borrow().streams.len() as u16
+    }
+
+    fn track_by_index<'a>(&'a self, index: u16) -> Box<container::Track + 'a> {
+        Box::new(TrackImpl {
+            demuxer: &self.demuxer,
+            index: index as usize,
+        }) as Box<container::Track + 'a>
+    }
+
+    fn track_by_number<'a>(&'a self, number: c_long) -> Box<container::Track + 'a> {
+        let index = self.demuxer.borrow().streams.iter().position(|stream| {
+            stream.serial_number as c_long == number
+        }).unwrap();
+        self.track_by_index(index as u16)
+    }
+}
+
+/// A track, which is simply a logical stream. Depending on the codec, this may be a video track or
+/// an audio track.
+#[derive(Clone)]
+struct TrackImpl<'a> {
+    demuxer: &'a RefCell<Demuxer>,
+    index: usize,
+}
+
+impl<'a> TrackImpl<'a> {
+    fn stream_codec(&self) -> StreamCodec {
+        self.demuxer.borrow().streams[self.index].codec
+    }
+
+    /// Returns the identification header, which every supported codec has.
+    fn id_header(&self) -> Vec<u8> {
+        self.demuxer.borrow().streams[self.index].headers[0].clone()
+    }
+}
+
+impl<'a> container::Track for TrackImpl<'a> {
+    fn track_type(&self) -> container::TrackType {
+        self.demuxer.borrow().streams[self.index].track_type()
+    }
+
+    fn cluster_count(&self) -> Option<c_int> {
+        Some(1)
+    }
+
+    fn number(&self) -> c_long {
+        self.demuxer.borrow().streams[self.index].serial_number as c_long
+    }
+
+    fn codec(&self) -> Option<Vec<u8>> {
+        match self.stream_codec() {
+            StreamCodec::Theora => Some(b"theo".to_vec()),
+            StreamCodec::Vorbis => Some(b"vorb".to_vec()),
+            StreamCodec::Unknown => None,
+        }
+    }
+
+    fn cluster<'b>(&'b self, cluster_index: i32) -> Result<Box<container::Cluster + 'b>,()> {
+        if cluster_index != 0 {
+            return Err(())
+        }
+        Ok(Box::new(ClusterImpl {
+            demuxer: self.demuxer,
+        }) as Box<container::Cluster + 'b>)
+    }
+
+    fn as_video_track<'b>(&'b self) -> Result<Box<container::VideoTrack + 'b>,()> {
+        if self.stream_codec() != StreamCodec::Theora {
+            return Err(())
+        }
+        Ok(Box::new((*self).clone()) as Box<container::VideoTrack + 'b>)
+    }
+
+    fn as_audio_track<'b>(&'b self) -> Result<Box<container::AudioTrack + 'b>,()> {
+        if self.stream_codec() != StreamCodec::Vorbis {
+            return Err(())
+        }
+        Ok(Box::new((*self).clone()) as Box<container::AudioTrack + 'b>)
+    }
+}
+
+impl<'a> container::VideoTrack for TrackImpl<'a> {
+    fn width(&self) -> u16 {
+        // This is the picture region, which is what should be displayed; the coded frame is
+        // rounded up to a multiple of 16.
+        read_u24_be(&self.id_header()[14..17]) as u16
+    }
+
+    fn height(&self) -> u16 {
+        read_u24_be(&self.id_header()[17..20]) as u16
+    }
+
+    fn frame_rate(&self) -> c_double {
+        let demuxer = self.demuxer.borrow();
+        let (numerator, denominator) = demuxer.streams[self.index].theora_frame_rate();
+        numerator as c_double / denominator as c_double
+    }
+
+    fn pixel_format(&self) -> PixelFormat<'static> {
+        PixelFormat::I420
+    }
+
+    fn headers(&self) -> Box<videodecoder::VideoHeaders> {
+        Box::new(TheoraHeaders {
+            headers: self.demuxer.borrow().streams[self.index].headers.clone(),
+        }) as Box<videodecoder::VideoHeaders>
+    }
+}
+
+impl<'a> container::AudioTrack for TrackImpl<'a> {
+    fn sampling_rate(&self) -> c_double {
+        read_u32_le(&self.id_header()[12..16]) as c_double
+    }
+
+    fn channels(&self) -> u16 {
+        self.id_header()[11] as u16
+    }
+
+    fn headers(&self) -> Box<audiodecoder::AudioHeaders> {
+        let demuxer = self.demuxer.borrow();
+        let headers = &demuxer.streams[self.index].headers;
+        let mut data = Vec::new();
+        for header in headers.iter() {
+            data.extend_from_slice(&header);
+        }
+        Box::new(VorbisHeaders {
+            data: data,
+            id_size: headers[0].len(),
+            comment_size: headers[1].len(),
+        }) as Box<audiodecoder::AudioHeaders>
+    }
+}
+
+/// Ogg has no notion of clusters, so the entire stream is treated as a single cluster.
+struct ClusterImpl<'a> {
+    demuxer: &'a RefCell<Demuxer>,
+}
+
+impl<'a> container::Cluster for ClusterImpl<'a> {
+    fn read_frame<'b>(&'b self, frame_index: i32, track_number: c_long)
+                      -> Result<Box<container::Frame + 'b>,()> {
+        let mut demuxer = self.demuxer.borrow_mut();
+        let stream_index = match demuxer.streams.iter().position(|stream| {
+            stream.serial_number as c_long == track_number
+        }) {
+            Some(stream_index) => stream_index,
+            None => return Err(()),
+        };
+        if frame_index < 0 || demuxer.streams[stream_index].codec == StreamCodec::Unknown {
+            return Err(())
+        }
+        if !try!(demuxer.read_packet(stream_index, frame_index as usize)) {
+            return Err(())
+        }
+        Ok(Box::new(FrameImpl {
+            demuxer: self.demuxer,
+            stream_index: stream_index,
+            packet_index: frame_index as usize,
+        }) as Box<container::Frame + 'b>)
+    }
+}
+
+struct FrameImpl<'a> {
+    demuxer: &'a RefCell<Demuxer>,
+    stream_index: usize,
+    packet_index: usize,
+}
+
+impl<'a> container::Frame for FrameImpl<'a> {
+    fn len(&self) -> c_long {
+        let demuxer = self.demuxer.borrow();
+        demuxer.streams[self.stream_index].packets[self.packet_index].data.len() as c_long
+    }
+
+    fn read(&self, buffer: &mut [u8]) -> Result<(),()> {
+        let demuxer = self.demuxer.borrow();
+        buffer.copy_from_slice(&demuxer.streams[self.stream_index].packets[self.packet_index].data);
+        Ok(())
+    }
+
+    fn track_number(&self) -> c_long {
+        self.demuxer.borrow().streams[self.stream_index].serial_number as c_long
+    }
+
+    fn time(&self) -> Timestamp {
+        self.demuxer.borrow().streams[self.stream_index].time(self.packet_index)
+    }
+
+    fn rendering_offset(&self) -> i64 {
+        0
+    }
 }
 
+fn read_u24_be(bytes: &[u8]) -> u32 {
+    ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | (bytes[2] as u32)
+}
+
+fn read_u32_be(bytes: &[u8]) -> u32 {
+    ((bytes[0] as u32) << 24) | read_u24_be(&bytes[1..4])
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    (bytes[0] as u32) | ((bytes[1] as u32) << 8) | ((bytes[2] as u32) << 16) |
+        ((bytes[3] as u32) << 24)
+}
+
+pub const CONTAINER_READER: container::RegisteredContainerReader =
+    container::RegisteredContainerReader {
+        mime_types: &[
+            "video/ogg",
+            "audio/ogg",
+            "application/ogg",
+        ],
+        read: ContainerReaderImpl::new,
+    };
+
 #[allow(missing_copy_implementations)]
 pub mod ffi {
     use libc::{c_char, c_int, c_long, c_uchar};
@@ -235,7 +916,9 @@ pub mod ffi {
         pub fn ogg_stream_packetout(os: *mut ogg_stream_state, op: *mut ogg_packet) -> c_int;
 
         pub fn ogg_page_serialno(og: *const ogg_page) -> c_int;
+        pub fn ogg_page_bos(og: *const ogg_page) -> c_int;
         pub fn ogg_page_eos(og: *const ogg_page) -> c_int;
+        pub fn ogg_page_granulepos(og: *const ogg_page) -> i64;
     }
 }
 
@@ -16,10 +16,11 @@ extern crate log;
 
 use libc::c_long;
 use media::audioformat::{ConvertAudioFormat, Float32Interleaved, Float32Planar};
+use media::audiosink::{AudioRingBuffer, AudioSink};
 use media::container::VideoTrack;
-use media::pixelformat::{ConvertPixelFormat, PixelFormat};
+use media::pixelformat::PixelFormat;
 use media::playback::Player;
-use media::videodecoder::DecodedVideoFrame;
+use media::videosink::{self, VideoSink, VideoSinkAdapter};
 use sdl2::audio::{AudioCallback, AudioDevice, AudioSpecDesired, AudioSpec};
 use sdl2::event::WindowEventId;
 use sdl2::keyboard::Keycode;
@@ -34,13 +35,15 @@ use std::mem;
 use std::fs::File;
 use std::thread::sleep;
 use std::slice;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 struct ExampleMediaPlayer {
     /// A reference timestamp at which playback began.
     playback_start_ticks: i64,
     /// A reference time in nanoseconds at which playback began.
     playback_start_wallclock_time: Instant,
+    /// Whether the user has paused playback.
+    paused: bool,
 }
 
 impl ExampleMediaPlayer {
@@ -48,6 +51,7 @@ impl ExampleMediaPlayer {
         ExampleMediaPlayer {
             playback_start_ticks: 0,
             playback_start_wallclock_time: Instant::now(),
+            paused: false,
         }
     }
 
@@ -67,6 +71,10 @@ impl ExampleMediaPlayer {
                     keycode: Some(Keycode::Escape),
                     ..
                 } => return false,
+                Event::KeyDown {
+                    keycode: Some(Keycode::Space),
+                    ..
+                } => self.paused = !self.paused,
                 Event::Window {
                     win_event_id: WindowEventId::Resized,
                     ..
@@ -88,6 +96,10 @@ struct ExampleVideoRenderer<'a> {
     renderer: &'a mut Renderer<'static>,
     /// The YUV texture we're using.
     texture: Texture,
+    /// The format of the texture.
+    video_format: SdlVideoFormat,
+    /// The height of the texture, which is the decoded height of the video.
+    video_height: u32,
 }
 
 impl<'a> ExampleVideoRenderer<'a> {
@@ -100,52 +112,94 @@ impl<'a> ExampleVideoRenderer<'a> {
         ExampleVideoRenderer {
             renderer: renderer,
             texture: texture,
+            video_format: video_format,
+            video_height: video_height,
         }
     }
 
-    fn present(&mut self, image: Box<DecodedVideoFrame + 'static>, player: &mut Player) {
-        let video_track_number = player.video_track_number().unwrap();
-        let reader = &mut *player.reader;
-        let video_track = reader.track_by_number(video_track_number as c_long);
-        let video_track = video_track.as_video_track().unwrap();
-
-        /*let rect = if let &RendererParent::Window(ref window) = self.renderer.get_parent() {
-            let (width, height) = window.get_size();
-            Rect::new(0, 0, width, height)
-        } else {
-            panic!("Renderer parent wasn't a window!")
-        };*/
-
-        self.upload(image, &*video_track);
-        self.renderer.copy(&self.texture, None, None).unwrap();
-        self.renderer.present();
-    }
-
-    fn upload(&mut self, image: Box<DecodedVideoFrame + 'static>, video_track: &VideoTrack) {
+    fn upload(&mut self,
+              input_pixels: &[&[u8]],
+              input_strides: &[usize],
+              pixel_format: PixelFormat,
+              width: usize,
+              height: usize) {
+        let media_pixel_format = self.video_format.media_pixel_format;
+        let width = cmp::min(width, self.video_format.sdl_width as usize);
+        let height = cmp::min(height, self.video_height as usize);
+        let texture_height = self.video_height as usize;
         drop(self.texture.with_lock(None, |pixels, stride| {
             // FIXME(pcwalton): Workaround for rust-sdl2#331: the pixels array may be too small.
-            let output_video_format = SdlVideoFormat::from_video_track(video_track);
-            let height = video_track.height() as usize;
-            let real_length = match output_video_format.media_pixel_format {
+            let real_length = match media_pixel_format {
                 PixelFormat::I420 => {
-                    stride as usize * height + 2 * ((stride / 2) as usize * (height / 2))
-                }
-                PixelFormat::Rgb24 => stride as usize * height,
-                _ => {
-                    panic!("SDL can't natively render in {:?}!",
-                           output_video_format.media_pixel_format)
+                    stride as usize * texture_height +
+                        2 * ((stride / 2) as usize * (texture_height / 2))
                 }
+                PixelFormat::Rgb24 => stride as usize * texture_height,
+                _ => panic!("SDL can't natively render in {:?}!", media_pixel_format),
             };
             let pixels = unsafe {
                 mem::transmute::<&mut [u8],
                                  &mut [u8]>(slice::from_raw_parts_mut(pixels.as_mut_ptr(),
                                                                     real_length))
             };
-            upload_image(video_track, &*image, pixels, stride as i32)
+
+            // Gather up the output pixels and strides.
+            let (mut output_pixels, output_strides) = match media_pixel_format {
+                PixelFormat::I420 => {
+                    let (output_luma, output_chroma) =
+                        pixels.split_at_mut(stride as usize * texture_height);
+                    let output_chroma_stride = stride as usize / 2;
+                    let (output_u, output_v) =
+                        output_chroma.split_at_mut(output_chroma_stride * (texture_height / 2));
+                    (vec![output_luma, output_u, output_v],
+                     vec![stride as usize, output_chroma_stride, output_chroma_stride])
+                }
+                _ => (vec![pixels], vec![stride as usize]),
+            };
+
+            // Copy the pixels over row by row, cropping to the size of the texture.
+            for plane in 0..pixel_format.planes() {
+                let (row_length, rows) = pixel_format.plane_dimensions(plane, width, height);
+                let row_length = cmp::min(row_length,
+                                          cmp::min(input_strides[plane], output_strides[plane]));
+                for row in 0..rows {
+                    let input_index = row * input_strides[plane];
+                    let output_index = row * output_strides[plane];
+                    output_pixels[plane][output_index..output_index + row_length]
+                        .copy_from_slice(&input_pixels[plane][input_index..
+                                                              input_index + row_length]);
+                }
+            }
         }));
     }
 }
 
+impl<'a> VideoSink for ExampleVideoRenderer<'a> {
+    fn supported_pixel_formats(&self) -> Vec<PixelFormat<'static>> {
+        vec![self.video_format.media_pixel_format]
+    }
+
+    fn present(&mut self,
+               pixels: &[&[u8]],
+               strides: &[usize],
+               pixel_format: PixelFormat,
+               width: usize,
+               height: usize)
+               -> Result<(),()> {
+        /*let rect = if let &RendererParent::Window(ref window) = self.renderer.get_parent() {
+            let (width, height) = window.get_size();
+            Rect::new(0, 0, width, height)
+        } else {
+            panic!("Renderer parent wasn't a window!")
+        };*/
+
+        self.upload(pixels, strides, pixel_format, width, height);
+        try!(self.renderer.copy(&self.texture, None, None).map_err(|_| ()));
+        self.renderer.present();
+        Ok(())
+    }
+}
+
 /// SDL cannot natively display all pixel formats that `rust-media` supports. Therefore we may have
 /// to do pixel format conversion ourselves. This structure contains the mapping from the pixel
 /// format of the codec to the nearest matching SDL format.
@@ -164,46 +218,37 @@ struct SdlVideoFormat {
 }
 
 impl SdlVideoFormat {
-    fn from_video_track(video_track: &VideoTrack) -> SdlVideoFormat {
-        let (media_pixel_format, sdl_pixel_format) = match video_track.pixel_format() {
-            PixelFormat::I420 | PixelFormat::NV12 => (PixelFormat::I420, PixelFormatEnum::IYUV),
-            PixelFormat::Indexed(_) | PixelFormat::Rgb24 => {
-                (PixelFormat::Rgb24, PixelFormatEnum::RGB24)
-            }
+    /// Chooses a format for the given track, whose frames decode to `width` pixels across.
+    fn from_video_track(video_track: &VideoTrack, width: u16) -> SdlVideoFormat {
+        let media_pixel_format =
+            videosink::negotiate_pixel_format(&video_track.pixel_format(),
+                                              &[PixelFormat::I420, PixelFormat::Rgb24]).unwrap();
+        let sdl_pixel_format = match media_pixel_format {
+            PixelFormat::I420 => PixelFormatEnum::IYUV,
+            _ => PixelFormatEnum::RGB24,
         };
         SdlVideoFormat {
             media_pixel_format: media_pixel_format,
             sdl_pixel_format: sdl_pixel_format,
-            sdl_width: video_track.width() & !1,
+            sdl_width: width & !1,
         }
     }
 }
 
 pub struct ExampleAudioRenderer {
-    samples: Vec<f32>,
+    samples: AudioRingBuffer,
     spec: AudioSpec,
 }
 
 impl AudioCallback for ExampleAudioRenderer {
     type Channel = f32;
     fn callback(&mut self, out: &mut [f32]) {
-        if self.samples.len() < out.len() {
-            // Zero out the buffer to avoid damaging the listener's eardrums.
+        // The ring buffer zeroes out whatever it can't fill, to avoid damaging the listener's
+        // eardrums.
+        let paused = self.samples.is_paused();
+        if self.samples.pop(out) < out.len() && !paused {
             warn!("audio underrun");
-            for value in out.iter_mut() {
-                *value = 0.0
-            }
         }
-
-        let mut leftovers = Vec::new();
-        for (i, sample) in mem::replace(&mut self.samples, Vec::new()).into_iter().enumerate() {
-            if i < out.len() {
-                out[i] = sample
-            } else {
-                leftovers.push(sample);
-            }
-        }
-        self.samples = leftovers
     }
 }
 
@@ -215,75 +260,38 @@ impl ExampleAudioRenderer {
             samples: None,
         };
         sdl_audio.open_playback(None, &desired_spec, |spec| ExampleAudioRenderer {
-            samples: Vec::new(),
+            samples: AudioRingBuffer::new(),
             spec: spec,
         }).unwrap()
     }
 }
 
-fn enqueue_audio_samples(device: &mut AudioDevice<ExampleAudioRenderer>,
-                         input_samples: &[Vec<f32>]) {
-    // Gather up all the channels so we can perform audio format conversion.
-    let input_samples: Vec<_> = input_samples.iter()
-                                             .take(2)
-                                             .map(|samples| samples.as_slice())
-                                             .collect();
-
-    // Make room for the samples in the output buffer.
-    let mut output = device.lock();
-    let channels = output.spec.channels;
-    let output_channels = cmp::min(channels, 2);
-    let output_index = output.samples.len();
-    let input_sample_count = input_samples[0].len();
-    let output_length = input_sample_count * output_channels as usize;
-    output.samples.resize(output_index + output_length, 0.0);
-
-    // Perform audio format conversion.
-    Float32Planar.convert(&Float32Interleaved,
-                          &mut [&mut output.samples[output_index..]],
-                          input_samples.as_slice(),
-                          output_channels as usize).unwrap();
+struct ExampleAudioSink {
+    /// The SDL audio device, which owns the ring buffer.
+    device: AudioDevice<ExampleAudioRenderer>,
 }
 
-fn upload_image(video_track: &VideoTrack,
-                image: &DecodedVideoFrame,
-                output_pixels: &mut [u8],
-                output_stride: i32) {
-    let height = video_track.height();
-    let pixel_format = image.pixel_format();
-
-    // Gather up all the input pixels and strides so we can do pixel format conversion.
-    let lock = image.lock();
-    let (mut input_pixels, mut input_strides) = (Vec::new(), Vec::new());
-    for plane in 0..pixel_format.planes() {
-        input_pixels.push(lock.pixels(plane));
-        input_strides.push(image.stride(plane) as usize);
+impl AudioSink for ExampleAudioSink {
+    fn enqueue(&mut self, input_samples: &[&[f32]]) -> Result<(),()> {
+        // Gather up all the channels so we can perform audio format conversion.
+        let input_samples: Vec<_> = input_samples.iter().take(2).map(|samples| *samples).collect();
+
+        // Perform audio format conversion.
+        let mut output = self.device.lock();
+        let output_channels = cmp::min(output.spec.channels, 2) as usize;
+        let mut interleaved_samples = vec![0.0; input_samples[0].len() * output_channels];
+        try!(Float32Planar.convert(&Float32Interleaved,
+                                   &mut [&mut interleaved_samples[..]],
+                                   input_samples.as_slice(),
+                                   output_channels));
+
+        output.samples.push(&interleaved_samples);
+        Ok(())
     }
 
-    // Gather up the output pixels and strides.
-    let output_video_format = SdlVideoFormat::from_video_track(&*video_track);
-    let (mut output_pixels, output_strides) = match output_video_format.media_pixel_format {
-        PixelFormat::I420 => {
-            let (output_luma, output_chroma) =
-                output_pixels.split_at_mut(output_stride as usize * height as usize);
-            let output_chroma_stride = output_stride as usize / 2;
-            let (output_u, output_v) =
-                output_chroma.split_at_mut(output_chroma_stride as usize * (height / 2) as usize);
-            (vec![output_luma, output_u, output_v],
-             vec![output_stride as usize, output_chroma_stride, output_chroma_stride])
-        }
-        PixelFormat::Rgb24 => (vec![output_pixels], vec![output_stride as usize]),
-        _ => panic!("SDL can't natively render in {:?}!", output_video_format.media_pixel_format),
-    };
-
-    // Perform pixel format conversion.
-    pixel_format.convert(&output_video_format.media_pixel_format,
-                         output_pixels.as_mut_slice(),
-                         output_strides.as_slice(),
-                         input_pixels.as_slice(),
-                         input_strides.as_slice(),
-                         output_video_format.sdl_width as usize,
-                         height as usize).unwrap();
+    fn set_paused(&mut self, paused: bool) {
+        self.device.lock().samples.set_paused(paused)
+    }
 }
 
 fn main() {
@@ -315,13 +323,22 @@ fn main() {
                                       .unwrap();
         window.renderer().accelerated().present_vsync().build().unwrap()
     });
+
+    // Decode the first frame before setting up the texture, which needs to be the decoded size of
+    // the video. This can differ from the size the container declares, which is what the window
+    // is sized to; SDL scales the texture to fit.
+    let mut decoded = player.decode_frame().is_ok();
     let mut video_renderer = player.video_track_number().map(|video_track_number| {
         let video_track = player.reader.track_by_number(video_track_number as c_long);
         let video_track = video_track.as_video_track().unwrap();
-        let video_format = SdlVideoFormat::from_video_track(&*video_track);
-        ExampleVideoRenderer::new(renderer.as_mut().unwrap(),
-                                  video_format,
-                                  video_track.height() as u32)
+        let (width, height) = match player.decoded_video_size() {
+            Some((width, height)) => (width as u16, height as u32),
+            None => (video_track.width(), video_track.height() as u32),
+        };
+        let video_format = SdlVideoFormat::from_video_track(&*video_track, width);
+        VideoSinkAdapter::new(ExampleVideoRenderer::new(renderer.as_mut().unwrap(),
+                                                        video_format,
+                                                        height))
     });
 
     let mut audio_renderer = player.audio_track_number().map(|audio_track_number| {
@@ -330,14 +347,12 @@ fn main() {
         let renderer = ExampleAudioRenderer::new(&sdl_audio, audio_track.sampling_rate(),
                                                  audio_track.channels());
         renderer.resume();
-        renderer
-    });
-
-    loop {
-        if player.decode_frame().is_err() {
-            break
+        ExampleAudioSink {
+            device: renderer,
         }
+    });
 
+    while decoded {
         let target_time_since_playback_start = (player.next_frame_presentation_time().unwrap() -
                                                 media_player.playback_start_ticks).duration();
         let target_time = media_player.playback_start_wallclock_time
@@ -347,21 +362,47 @@ fn main() {
             sleep(target_time - now);
         }
 
-        let frame = match player.advance() {
+        let mut frame = match player.advance() {
             Ok(frame) => frame,
             Err(_) => break,
         };
 
         if let Some(ref mut video_renderer) = video_renderer {
-            video_renderer.present(frame.video_frame.unwrap(), &mut player);
+            let video_track_number = player.video_track_number().unwrap();
+            video_renderer.present(&*frame.take_video_frame(video_track_number).unwrap()).unwrap();
         }
         if let Some(ref mut audio_renderer) = audio_renderer {
-            enqueue_audio_samples(audio_renderer, frame.audio_samples.unwrap().as_slice());
+            let audio_samples = frame.audio_samples.unwrap();
+            let audio_samples: Vec<_> = audio_samples.iter()
+                                                     .map(|samples| samples.as_slice())
+                                                     .collect();
+            audio_renderer.enqueue(&audio_samples).unwrap();
         }
 
         if !media_player.poll_events(&mut event_pump, &mut player) {
             break
         }
+
+        // If the user paused, hold the audio where it is until they resume.
+        if media_player.paused {
+            if let Some(ref mut audio_renderer) = audio_renderer {
+                audio_renderer.set_paused(true)
+            }
+            while media_player.paused {
+                sleep(Duration::from_millis(10));
+                if !media_player.poll_events(&mut event_pump, &mut player) {
+                    return
+                }
+            }
+            if let Some(ref mut audio_renderer) = audio_renderer {
+                audio_renderer.set_paused(false)
+            }
+            if let Some(last_frame_time) = player.last_frame_presentation_time() {
+                media_player.resync(last_frame_time.ticks)
+            }
+        }
+
+        decoded = player.decode_frame().is_ok();
     }
 }
 
@@ -25,16 +25,19 @@ extern crate core_foundation;
 
 pub mod audiodecoder;
 pub mod audioformat;
+pub mod audiosink;
 pub mod container;
 pub mod pixelformat;
 pub mod playback;
 pub mod streaming;
 pub mod timing;
 pub mod videodecoder;
+pub mod videosink;
 
 pub mod codecs {
     pub mod aac;
     pub mod h264;
+    pub mod theora;
     pub mod vorbis;
     pub mod vpx;
 
@@ -167,24 +167,38 @@ impl ConvertPixelFormat<Rgb24> for I420 {
                width: usize,
                height: usize)
                -> Result<(),()> {
-        // FIXME(pcwalton): This does not convert the chroma yet. Dorothy has not yet left Kansas.
         let (y_input_pixels, y_input_stride) = (input_pixels[0], input_strides[0]);
-        let (mut input_index, mut output_index) = (0, 0);
-        for _ in range(0, height) {
-            let input_row = &y_input_pixels[input_index..input_index + width * 3];
-            let output_row =
-                &mut output_pixels[0][output_index..output_index + output_strides[0]];
-            let mut writer = BufWriter::new(output_row);
+        let (u_input_pixels, u_input_stride) = (input_pixels[1], input_strides[1]);
+        let (v_input_pixels, v_input_stride) = (input_pixels[2], input_strides[2]);
+        for y in range(0, height) {
+            let y_input_row = &y_input_pixels[y * y_input_stride..y * y_input_stride + width];
+            let u_input_row = &u_input_pixels[y / 2 * u_input_stride..];
+            let v_input_row = &v_input_pixels[y / 2 * v_input_stride..];
+            let output_index = y * output_strides[0];
+            let output_row = &mut output_pixels[0][output_index..output_index + width * 3];
             for x in range(0, width) {
-                drop(writer.write_all(&[input_row[x], input_row[x], input_row[x]]));
+                let color = yuv_to_rgb(y_input_row[x], u_input_row[x / 2], v_input_row[x / 2]);
+                output_row[x * 3] = color.r;
+                output_row[x * 3 + 1] = color.g;
+                output_row[x * 3 + 2] = color.b;
             }
-            input_index += y_input_stride;
-            output_index += output_strides[0];
         }
         Ok(())
     }
 }
 
+/// Converts a color with BT.601 "studio swing" Y'CbCr components, as produced by nearly all video
+/// codecs, to RGB.
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> RgbColor {
+    let (c, d, e) = (y as i32 - 16, u as i32 - 128, v as i32 - 128);
+    let clamp = |value: i32| cmp::max(0, cmp::min(255, (value + 128) >> 8)) as u8;
+    RgbColor {
+        r: clamp(298 * c + 409 * e),
+        g: clamp(298 * c - 100 * d - 208 * e),
+        b: clamp(298 * c + 516 * d),
+    }
+}
+
 impl<'a> ConvertPixelFormat<Rgb24> for Palette<'a> {
     fn convert(&self,
                _: &Rgb24,
@@ -340,5 +354,35 @@ impl<'a> PixelFormat<'a> {
             PixelFormat::Indexed(_) | PixelFormat::Rgb24 => 1,
         }
     }
+
+    /// Returns the number of bytes per row and the number of rows in the given plane of an image
+    /// of the given size in this pixel format, assuming tightly packed rows.
+    pub fn plane_dimensions(&self, plane_index: usize, width: usize, height: usize)
+                            -> (usize, usize) {
+        match (*self, plane_index) {
+            (PixelFormat::I420, 0) | (PixelFormat::NV12, 0) | (PixelFormat::Indexed(_), _) => {
+                (width, height)
+            }
+            (PixelFormat::I420, _) => ((width + 1) / 2, (height + 1) / 2),
+            (PixelFormat::NV12, _) => ((width + 1) / 2 * 2, (height + 1) / 2),
+            (PixelFormat::Rgb24, _) => (width * 3, height),
+        }
+    }
+
+    /// Returns a rough relative cost of converting an image in this pixel format to the given
+    /// pixel format with `convert()`, or `None` if that conversion is unsupported. A cost of zero
+    /// means that the formats match and the pixels can be used as-is.
+    pub fn conversion_cost(&self, to: &PixelFormat) -> Option<u32> {
+        match (*self, *to) {
+            (PixelFormat::I420, PixelFormat::I420) |
+            (PixelFormat::NV12, PixelFormat::NV12) |
+            (PixelFormat::Indexed(_), PixelFormat::Indexed(_)) |
+            (PixelFormat::Rgb24, PixelFormat::Rgb24) => Some(0),
+            (PixelFormat::NV12, PixelFormat::I420) => Some(1),
+            (PixelFormat::I420, PixelFormat::Rgb24) |
+            (PixelFormat::Indexed(_), PixelFormat::Rgb24) => Some(2),
+            (_, _) => None,
+        }
+    }
 }
 
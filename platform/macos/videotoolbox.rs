@@ -9,7 +9,8 @@
 
 use codecs::h264;
 use platform::macos::coremedia::{self, CMBlockBuffer, CMFormatDescription, CMSampleBuffer};
-use platform::macos::coremedia::{CMSampleTimingInfo, CMTime, OSStatus, kCMVideoCodecType_H264};
+use platform::macos::coremedia::{CMSampleTimingInfo, CMTime, CMVideoCodecType, OSStatus};
+use platform::macos::coremedia::kCMVideoCodecType_H264;
 use platform::macos::corevideo::{CVBuffer, DecodedFrameImpl};
 use platform::macos::corevideo::ffi::CVImageBufferRef;
 use timing::Timestamp;
@@ -20,7 +21,7 @@ use core_foundation::base::{kCFAllocatorDefault};
 use core_foundation::data::CFData;
 use core_foundation::dictionary::CFDictionary;
 use core_foundation::string::CFString;
-use libc::c_void;
+use libc::{self, c_char, c_void};
 use std::cell::RefCell;
 use std::mem;
 use std::ptr;
@@ -154,6 +155,21 @@ extern "C" fn decompression_output_callback(decompression_output_ref_con: *mut c
     }
 }
 
+/// Returns whether there's a hardware decoder for the given codec. `VTIsHardwareDecodeSupported()`
+/// only exists on macOS 10.13 and later, so it's looked up at runtime, and support is assumed if
+/// it's missing.
+fn is_hardware_decode_supported(codec_type: CMVideoCodecType) -> bool {
+    unsafe {
+        let symbol = libc::dlsym(libc::RTLD_DEFAULT,
+                                 b"VTIsHardwareDecodeSupported\0".as_ptr() as *const c_char);
+        if symbol.is_null() {
+            return true
+        }
+        let function: ffi::VTIsHardwareDecodeSupported = mem::transmute(symbol);
+        function(codec_type) != 0
+    }
+}
+
 // Implementation of the abstract `VideoDecoder` interface
 
 struct VideoDecoderImpl {
@@ -163,6 +179,26 @@ struct VideoDecoderImpl {
 }
 
 impl VideoDecoderImpl {
+    /// VideoToolbox handles 8-bit 4:2:0 H.264 up to level 5.2. Support is only claimed if there's
+    /// a hardware decoder (or if the system is too old to say), since otherwise FFmpeg (if
+    /// present) is the better choice; if nothing else can handle the stream, the registry falls
+    /// back to VideoToolbox anyway.
+    fn supports(profile: Option<u32>, level: Option<u32>, _: i32, _: i32) -> bool {
+        let profile_supported = match profile {
+            None => true,
+            Some(profile) => {
+                profile == h264::PROFILE_BASELINE || profile == h264::PROFILE_MAIN ||
+                    profile == h264::PROFILE_HIGH
+            }
+        };
+        let level_supported = match level {
+            None => true,
+            Some(level) => level <= 52,
+        };
+        profile_supported && level_supported &&
+            is_hardware_decode_supported(kCMVideoCodecType_H264)
+    }
+
     fn new(headers: &videodecoder::VideoHeaders, width: i32, height: i32)
            -> Result<Box<videodecoder::VideoDecoder + 'static>,()> {
         // Create the video format description.
@@ -273,16 +309,17 @@ pub const VIDEO_DECODER: videodecoder::RegisteredVideoDecoder =
     videodecoder::RegisteredVideoDecoder {
         id: [ b'a', b'v', b'c', b' ' ],
         constructor: VideoDecoderImpl::new,
+        supports: VideoDecoderImpl::supports,
     };
 
 #[allow(non_snake_case)]
 pub mod ffi {
-    use platform::macos::coremedia::{CMTime, OSStatus};
+    use platform::macos::coremedia::{CMTime, CMVideoCodecType, OSStatus};
     use platform::macos::coremedia::ffi::{CMSampleBufferRef, CMVideoFormatDescriptionRef};
     use platform::macos::corevideo::ffi::CVImageBufferRef;
     use platform::macos::videotoolbox::{VTDecodeFrameFlags, VTDecodeInfoFlags};
 
-    use core_foundation::base::{CFAllocatorRef, CFTypeID};
+    use core_foundation::base::{Boolean, CFAllocatorRef, CFTypeID};
     use core_foundation::dictionary::CFDictionaryRef;
     use libc::c_void;
 
@@ -306,6 +343,9 @@ pub mod ffi {
                                                            presentationTimeStamp: CMTime,
                                                            presentationDuration: CMTime);
 
+    /// The type of `VTIsHardwareDecodeSupported()`, which has to be looked up at runtime.
+    pub type VTIsHardwareDecodeSupported = extern "C" fn(codecType: CMVideoCodecType) -> Boolean;
+
     #[link(name="VideoToolbox", kind="framework")]
     extern {
         pub fn VTDecompressionSessionGetTypeID() -> CFTypeID;
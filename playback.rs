@@ -8,23 +8,39 @@
 // except according to those terms.
 
 use audiodecoder::{AudioDecoder, RegisteredAudioDecoder};
-use container::{ContainerReader, Frame, RegisteredContainerReader, TrackType};
+use codecs::h264;
+use container::{AudioTrack, Cluster, ContainerReader, Frame, RegisteredContainerReader};
+use container::{TrackType, VideoTrack};
 use streaming::StreamReader;
 use timing::Timestamp;
 use videodecoder::{DecodedVideoFrame, RegisteredVideoDecoder, VideoDecoder};
 
-use libc::{c_int, c_long};
+use libc::{c_int, c_long, c_uint};
 use num::iter::range;
+use std::cmp;
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
+use time::Duration;
+
+/// The default maximum distance, in milliseconds, between a decoded video frame's presentation time
+/// and the time at which the next frame is expected for that frame to be considered the next one.
+/// This leaves room for the rounding of frame times in WebM, whose timecodes are usually in
+/// milliseconds.
+const DEFAULT_NEXT_FRAME_TOLERANCE_MS: i64 = 5;
+
+/// The default distance, in milliseconds, past the time at which the next frame is expected beyond
+/// which a decoded video frame is considered to be in the far future (for example, after a gap in
+/// the stream).
+const DEFAULT_FAR_FUTURE_THRESHOLD_MS: i64 = 100;
 
 /// A simple video/audio player.
 pub struct Player<'a> {
     /// The container.
     pub reader: Box<ContainerReader + 'static>,
-    /// Information about the video track that's playing.
-    video: Option<VideoPlayerInfo>,
+    /// Information about the video tracks that are playing. The first of these, if any, is the
+    /// primary video track, which determines when frames are shown.
+    video: Vec<VideoPlayerInfo>,
     /// Information about the audio track that's playing.
     audio: Option<AudioPlayerInfo>,
     /// The index of the current cluster.
@@ -36,6 +52,15 @@ pub struct Player<'a> {
     last_frame_presentation_time: Option<Timestamp>,
     /// The time at which the next frame is to be played.
     next_frame_presentation_time: Option<Timestamp>,
+    /// How close to its expected presentation time a decoded video frame must be in order to be
+    /// shown as the next frame.
+    next_frame_tolerance: Duration,
+    /// How far past its expected presentation time a decoded video frame must be in order to stop
+    /// looking for a closer one.
+    far_future_threshold: Duration,
+    /// If set, the number of ticks per second to use for frame times in place of the one the
+    /// container reports.
+    time_scale_override: Option<f64>,
     marker: PhantomData<&'a ()>,
 }
 
@@ -52,41 +77,36 @@ impl<'a> Player<'a> {
             Ok(container_reader) => container_reader,
             Err(_) => return Err(PlayerCreationError::NoRegisteredContainer),
         };
-        let mut reader = match container_reader.new(reader) {
+        let reader = match container_reader.new(reader) {
             Ok(reader) => reader,
             Err(_) => return Err(PlayerCreationError::ContainerCreation),
         };
 
-        let (video_player_info, audio_player_info) = {
-            let (video_codec, audio_codec) =
-                read_track_metadata_and_initialize_codecs(&mut *reader);
-
-            let (mut video_track, mut audio_track) = (None, None);
-            for track_index in 0..reader.track_count() {
-                let track = reader.track_by_index(track_index);
-                if track.track_type() == TrackType::Video && video_track.is_none() {
-                    video_track = Some(track)
-                } else if track.track_type() == TrackType::Audio && audio_track.is_none() {
-                    audio_track = Some(track)
-                }
-            }
-
-            (video_track.map(|video_track| {
-                VideoPlayerInfo {
-                    codec: video_codec.unwrap(),
-                    track_number: video_track.number() as i64,
-                    frames: Vec::new(),
-                    frame_index: 0,
+        // Play the first video track and the first audio track that we have decoders for. Tracks
+        // in codecs that weren't built in (Theora without FFmpeg, for example) are skipped.
+        let (mut video_player_info, mut audio_player_info) = (Vec::new(), None);
+        for track_index in 0..reader.track_count() {
+            let track = reader.track_by_index(track_index);
+            match track.track_type() {
+                TrackType::Video if video_player_info.is_empty() => {
+                    if let Ok(codec) = create_video_codec(&*track.as_video_track().unwrap()) {
+                        video_player_info.push(VideoPlayerInfo::new(codec, track.number() as i64))
+                    }
                 }
-            }), audio_track.map(|audio_track| {
-                AudioPlayerInfo {
-                    codec: audio_codec.unwrap(),
-                    track_number: audio_track.number() as i64,
-                    samples: None,
-                    frame_index: 0,
+                TrackType::Audio if audio_player_info.is_none() => {
+                    if let Ok(codec) = create_audio_codec(&*track.as_audio_track().unwrap()) {
+                        audio_player_info = Some(AudioPlayerInfo {
+                            codec: codec,
+                            track_number: track.number() as i64,
+                            samples: None,
+                            frame_index: 0,
+                            samples_to_skip: 0,
+                        })
+                    }
                 }
-            }))
-        };
+                _ => {}
+            }
+        }
 
         Ok(Player {
             reader: reader,
@@ -96,13 +116,16 @@ impl<'a> Player<'a> {
             frame_delay: None,
             last_frame_presentation_time: None,
             next_frame_presentation_time: None,
+            next_frame_tolerance: Duration::milliseconds(DEFAULT_NEXT_FRAME_TOLERANCE_MS),
+            far_future_threshold: Duration::milliseconds(DEFAULT_FAR_FUTURE_THRESHOLD_MS),
+            time_scale_override: None,
             marker: PhantomData,
         })
     }
 
     pub fn decode_frame(&mut self) -> Result<(),()> {
         let reader = &mut *self.reader;
-        let video_track = self.video.as_ref().map(|video| {
+        let video_track = self.video.first().map(|video| {
             reader.track_by_number(video.track_number as c_long)
         });
         let video_track = video_track.as_ref().map(|track| track.as_video_track().unwrap());
@@ -128,7 +151,7 @@ impl<'a> Player<'a> {
             };
 
             // Read the video frame or frames.
-            if let Some(ref mut video) = self.video {
+            if let Some((video, other_videos)) = self.video.split_first_mut() {
                 loop {
                     match self.frame_delay {
                         None => {
@@ -138,12 +161,27 @@ impl<'a> Player<'a> {
                         }
                         Some(frame_delay) => {
                             let last_frame_time = self.last_frame_presentation_time.unwrap();
+                            let next_frame_tolerance = self.next_frame_tolerance;
+                            let far_future_threshold = self.far_future_threshold;
                             if video.frames.iter().any(|frame| {
                                 let last_frame_time = last_frame_time.ticks;
-                                let delta = frame.presentation_time().ticks - (last_frame_time +
-                                                                               frame_delay);
-                                let is_next_frame = delta.abs() < 5;
-                                let is_in_far_future = delta > 1000;
+                                let presentation_time = frame.presentation_time();
+                                let ticks_per_second = presentation_time.ticks_per_second;
+                                let delta = presentation_time.ticks - (last_frame_time +
+                                                                       frame_delay);
+                                // At coarse time scales (GIF, for instance, counts in
+                                // hundredths of a second), the tolerance can round down to zero
+                                // ticks, which would reject even an exact match. Clamp it so that
+                                // an exact match always counts.
+                                let next_frame_tolerance =
+                                    cmp::max(Timestamp::from_duration(next_frame_tolerance,
+                                                                      ticks_per_second).ticks,
+                                             1);
+                                let far_future_threshold =
+                                    Timestamp::from_duration(far_future_threshold,
+                                                             ticks_per_second).ticks;
+                                let is_next_frame = delta.abs() < next_frame_tolerance;
+                                let is_in_far_future = delta > far_future_threshold;
                                 is_next_frame || is_in_far_future
                             }) {
                                 break
@@ -153,10 +191,14 @@ impl<'a> Player<'a> {
 
                     // Read a video frame.
                     match cluster.read_frame(video.frame_index, video.track_number as c_long) {
-                        Ok(frame) => {
-                            decode_video_frame(&mut *video.codec, &*frame, &mut video.frames)
-                        }
+                        Ok(frame) => video.decode_frame(&*frame, self.time_scale_override),
                         Err(_) => {
+                            // Finish off the other video tracks before moving on to the next
+                            // cluster, so that none of their frames are skipped.
+                            for other_video in other_videos.iter_mut() {
+                                other_video.read_frames(&*cluster, None, self.time_scale_override);
+                                other_video.frame_index = 0
+                            }
                             self.cluster_index += 1;
                             video.frame_index = 0;
                             if let Some(ref mut audio) = self.audio {
@@ -189,6 +231,13 @@ impl<'a> Player<'a> {
                         None => continue,
                         Some(frame) => Some(frame.presentation_time()),
                     };
+
+                // Catch the other video tracks up to that time.
+                for other_video in other_videos.iter_mut() {
+                    other_video.read_frames(&*cluster,
+                                            self.next_frame_presentation_time,
+                                            self.time_scale_override)
+                }
             }
 
             // Read the audio frame or frames.
@@ -206,17 +255,19 @@ impl<'a> Player<'a> {
                     decode_audio_frame(&mut *audio.codec,
                                        &*frame,
                                        &mut audio.samples.as_mut().unwrap());
+                    audio.skip_leading_samples();
                     audio.frame_index += 1;
 
                     // If there is a video track, we synchronize to it. Otherwise, read just one
                     // audio frame.
-                    if self.video.is_some() {
-                        if frame.time().duration() >=
+                    let time = frame_time(&*frame, self.time_scale_override);
+                    if !self.video.is_empty() {
+                        if time.duration() >=
                                 self.next_frame_presentation_time.unwrap().duration() {
                             break
                         }
                     } else {
-                        self.next_frame_presentation_time = Some(frame.time());
+                        self.next_frame_presentation_time = Some(time);
                         break
                     }
                 }
@@ -226,9 +277,54 @@ impl<'a> Player<'a> {
         }
     }
 
-    /// Returns the number of the video track, if present.
+    /// Returns the number of the primary video track, if present.
     pub fn video_track_number(&self) -> Option<i64> {
-        self.video.as_ref().map(|video| video.track_number)
+        self.video.first().map(|video| video.track_number)
+    }
+
+    /// Returns the numbers of all the video tracks being decoded, starting with the primary one.
+    pub fn video_track_numbers(&self) -> Vec<i64> {
+        self.video.iter().map(|video| video.track_number).collect()
+    }
+
+    /// Starts decoding the video track with the given number alongside any others, for files that
+    /// carry more than one (for example, a thumbnail track or a second stereoscopic view). Its
+    /// frames are returned from `advance()` along with those of the other video tracks.
+    ///
+    /// If no video track is being decoded yet, this one becomes the primary video track, which
+    /// determines when frames are shown; by default, the primary video track is the first video
+    /// track in the container.
+    ///
+    /// Returns an error if there is no such video track, if it's already being decoded, or if
+    /// there is no decoder for its codec.
+    pub fn add_video_output(&mut self, track_number: i64) -> Result<(),()> {
+        if self.video.iter().any(|video| video.track_number == track_number) {
+            return Err(())
+        }
+        let codec = {
+            let reader = &*self.reader;
+            let track = match (0..reader.track_count()).map(|index| reader.track_by_index(index))
+                                                       .find(|track| {
+                track.number() as i64 == track_number
+            }) {
+                Some(track) => track,
+                None => return Err(()),
+            };
+            if track.track_type() != TrackType::Video {
+                return Err(())
+            }
+            try!(create_video_codec(&*try!(track.as_video_track())))
+        };
+        self.video.push(VideoPlayerInfo::new(codec, track_number));
+        Ok(())
+    }
+
+    /// Returns the coded width and height of the primary video track, as reported by the decoder.
+    /// This is `None` until the first video frame has been decoded. It may differ from the
+    /// declared size of the video track, which is the size the container says the video should be
+    /// displayed at.
+    pub fn decoded_video_size(&self) -> Option<(c_uint, c_uint)> {
+        self.video.first().and_then(|video| video.decoded_size)
     }
 
     /// Returns the number of the audio track, if present.
@@ -236,6 +332,146 @@ impl<'a> Player<'a> {
         self.audio.as_ref().map(|audio| audio.track_number)
     }
 
+    /// Sets how close to its expected presentation time a decoded video frame must be in order to
+    /// be shown as the next frame. Frames that fall outside this window are buffered until a
+    /// better match is decoded. The default is 5 ms.
+    pub fn set_next_frame_tolerance(&mut self, tolerance: Duration) {
+        self.next_frame_tolerance = tolerance
+    }
+
+    /// Sets how far past its expected presentation time a decoded video frame must be in order to
+    /// give up looking for a closer one. The default is 100 ms.
+    pub fn set_far_future_threshold(&mut self, threshold: Duration) {
+        self.far_future_threshold = threshold
+    }
+
+    /// Overrides the number of ticks per second used to interpret the times of frames read from
+    /// the container. This is an escape hatch for files known to have a broken time scale (for
+    /// example, one that claims 1000 ticks per second when it's really 1000000), which otherwise
+    /// play back at the wrong speed. It applies uniformly to all tracks, so it should only be used
+    /// on known-bad inputs. Frames that have already been decoded are unaffected.
+    ///
+    /// Returns an error, leaving the time scale alone, if `ticks_per_second` isn't a positive
+    /// finite number.
+    pub fn set_time_scale_override(&mut self, ticks_per_second: f64) -> Result<(),()> {
+        if !(ticks_per_second > 0.0) || !ticks_per_second.is_finite() {
+            return Err(())
+        }
+        self.time_scale_override = Some(ticks_per_second);
+        Ok(())
+    }
+
+    /// Moves the audio track to `target`, relative to the start of the stream. Audio is positioned
+    /// exactly: the frame containing the target is decoded, and the samples before the target are
+    /// thrown away, so the first samples returned afterward start at the target.
+    ///
+    /// This is only the audio half of seeking. Video tracks share the audio track's position in
+    /// the stream, so this returns an error if any are being decoded. It also returns an error if
+    /// there is no audio track, or if `target` precedes the start of its first frame or is at or
+    /// past the end of its last one.
+    ///
+    /// TODO: Add a seek that moves video tracks to the keyframe at or before the target and then
+    /// positions the audio the same way.
+    pub fn seek_audio(&mut self, target: Duration) -> Result<(),()> {
+        if !self.video.is_empty() {
+            return Err(())
+        }
+
+        let time_scale_override = self.time_scale_override;
+        let reader = &*self.reader;
+        let audio = match self.audio {
+            Some(ref mut audio) => audio,
+            None => return Err(()),
+        };
+        let track = reader.track_by_number(audio.track_number as c_long);
+        let audio_track = try!(track.as_audio_track());
+        let track_number = audio.track_number as c_long;
+
+        // Find the last frame that starts at or before the target, as well as the one before
+        // that, and note whether any frame follows it.
+        //
+        // FIXME: This is a linear scan. Use the container's index, if it has one.
+        let (mut position, mut previous_position) = (None, None);
+        let mut is_last_frame = true;
+        let mut cluster_index = 0;
+        'search: loop {
+            if let Some(cluster_count) = audio_track.cluster_count() {
+                if cluster_index >= cluster_count {
+                    break
+                }
+            }
+            let cluster = match audio_track.cluster(cluster_index) {
+                Ok(cluster) => cluster,
+                Err(_) => break,
+            };
+            let mut frame_index = 0;
+            while let Ok(frame) = cluster.read_frame(frame_index, track_number) {
+                let frame_start = frame_time(&*frame, time_scale_override).duration();
+                if frame_start > target {
+                    is_last_frame = false;
+                    break 'search
+                }
+                previous_position = position;
+                position = Some((cluster_index, frame_index, frame_start));
+                frame_index += 1;
+            }
+            cluster_index += 1;
+        }
+        let (cluster_index, frame_index, frame_start) = match position {
+            Some(position) => position,
+            None => return Err(()),
+        };
+
+        // Round to the nearest sample, since frame times lose a fraction of a nanosecond when
+        // they're converted to durations. The difference overflows only if the target is far past
+        // the end of the stream.
+        let offset = match (target - frame_start).num_nanoseconds() {
+            Some(nanoseconds) => {
+                (nanoseconds as f64 * audio_track.sampling_rate() / 1_000_000_000.0).round()
+            }
+            None => return Err(()),
+        };
+
+        // Prime the decoder with the preceding frame, throwing away its output. Codecs that
+        // overlap adjacent frames, such as Vorbis and AAC, need this to decode the target frame
+        // correctly.
+        let channels = audio_track.channels() as usize;
+        let prime = |codec: &mut AudioDecoder| -> Result<(),()> {
+            if let Some((previous_cluster_index, previous_frame_index, _)) = previous_position {
+                let cluster = try!(audio_track.cluster(previous_cluster_index));
+                let frame = try!(cluster.read_frame(previous_frame_index, track_number));
+                let mut samples: Vec<_> = iter::repeat(Vec::new()).take(channels).collect();
+                decode_audio_frame(codec, &*frame, &mut samples);
+            }
+            Ok(())
+        };
+        try!(prime(&mut *audio.codec));
+
+        // Nothing marks where the last frame ends, so decode it to find out whether the target
+        // lies within it. Then prime the decoder again, so that it can be decoded once more
+        // during playback.
+        if is_last_frame {
+            let cluster = try!(audio_track.cluster(cluster_index));
+            let frame = try!(cluster.read_frame(frame_index, track_number));
+            let mut samples: Vec<_> = iter::repeat(Vec::new()).take(channels).collect();
+            decode_audio_frame(&mut *audio.codec, &*frame, &mut samples);
+            let sample_count = samples.iter().map(|channel| channel.len()).min().unwrap_or(0);
+            if offset >= sample_count as f64 {
+                return Err(())
+            }
+            try!(prime(&mut *audio.codec));
+        }
+
+        audio.frame_index = frame_index;
+        audio.samples = None;
+        audio.samples_to_skip = offset as usize;
+        self.cluster_index = cluster_index;
+        self.frame_delay = None;
+        self.last_frame_presentation_time = None;
+        self.next_frame_presentation_time = None;
+        Ok(())
+    }
+
     /// Returns the presentation time of the last frame, relative to the start of playback.
     pub fn last_frame_presentation_time(&self) -> Option<Timestamp> {
         self.last_frame_presentation_time
@@ -258,8 +494,8 @@ impl<'a> Player<'a> {
         self.last_frame_presentation_time = self.next_frame_presentation_time;
 
         // Determine which video frame to show.
-        let index = match self.video {
-            Some(ref mut video) => {
+        let index = match self.video.first() {
+            Some(video) => {
                 match video.frames
                            .iter()
                            .enumerate()
@@ -271,11 +507,23 @@ impl<'a> Player<'a> {
             None => None,
         };
 
-        // Extract and return the frame.
+        // Extract the frame, along with whichever frames of the other video tracks are due by the
+        // time it's shown.
+        let mut video_frames = Vec::new();
+        if let Some((video, other_videos)) = self.video.split_first_mut() {
+            let frame = video.frames.remove(index.unwrap());
+            let time = frame.presentation_time().duration();
+            video_frames.push((video.track_number, frame));
+            for other_video in other_videos.iter_mut() {
+                if let Some(other_frame) = other_video.take_frame_due_by(time) {
+                    video_frames.push((other_video.track_number, other_frame))
+                }
+            }
+        }
+
+        // Return the frames.
         Ok(DecodedFrame {
-            video_frame: self.video.as_mut().map(|video| {
-                video.frames.remove(index.unwrap())
-            }),
+            video_frames: video_frames,
             audio_samples: self.audio.as_mut().map(|audio| {
                 mem::replace(&mut audio.samples, None).unwrap()
             })
@@ -293,6 +541,77 @@ struct VideoPlayerInfo {
     frames: Vec<Box<DecodedVideoFrame + 'static>>,
     /// The index of the current frame.
     frame_index: i32,
+    /// The coded size of the video, once the first frame has been decoded.
+    decoded_size: Option<(c_uint, c_uint)>,
+}
+
+impl VideoPlayerInfo {
+    fn new(codec: Box<VideoDecoder + 'static>, track_number: i64) -> VideoPlayerInfo {
+        VideoPlayerInfo {
+            codec: codec,
+            track_number: track_number,
+            frames: Vec::new(),
+            frame_index: 0,
+            decoded_size: None,
+        }
+    }
+
+    /// Decodes a frame and buffers the result.
+    fn decode_frame(&mut self, frame: &Frame, time_scale_override: Option<f64>) {
+        decode_video_frame(&mut *self.codec, frame, time_scale_override, &mut self.frames);
+
+        // Record the real size of the video the first time we see it.
+        if self.decoded_size.is_none() {
+            self.decoded_size = self.frames.last().map(|image| (image.width(), image.height()))
+        }
+    }
+
+    /// Decodes frames from the given cluster until a frame to be presented at or after `until` has
+    /// been buffered, or, if `until` is `None`, until the end of the cluster.
+    fn read_frames(&mut self,
+                   cluster: &Cluster,
+                   until: Option<Timestamp>,
+                   time_scale_override: Option<f64>) {
+        loop {
+            if let Some(until) = until {
+                if self.frames.iter().any(|frame| {
+                    frame.presentation_time().duration() >= until.duration()
+                }) {
+                    break
+                }
+            }
+            let frame = match cluster.read_frame(self.frame_index, self.track_number as c_long) {
+                Ok(frame) => frame,
+                Err(_) => break,
+            };
+            self.decode_frame(&*frame, time_scale_override);
+            self.frame_index += 1;
+        }
+    }
+
+    /// Removes and returns the latest buffered frame that is due to be presented by `time`. Any
+    /// earlier frames have been superseded by it and are thrown out.
+    fn take_frame_due_by(&mut self, time: Duration) -> Option<Box<DecodedVideoFrame + 'static>> {
+        let mut result: Option<Box<DecodedVideoFrame + 'static>> = None;
+        let mut i = 0;
+        while i < self.frames.len() {
+            if self.frames[i].presentation_time().duration() > time {
+                i += 1;
+                continue
+            }
+            let frame = self.frames.remove(i);
+            let is_later = match result {
+                None => true,
+                Some(ref result) => {
+                    frame.presentation_time().duration() >= result.presentation_time().duration()
+                }
+            };
+            if is_later {
+                result = Some(frame)
+            }
+        }
+        result
+    }
 }
 
 /// Information about a playing audio track.
@@ -305,55 +624,100 @@ struct AudioPlayerInfo {
     samples: Option<Vec<Vec<f32>>>,
     /// The index of the current frame.
     frame_index: i32,
+    /// The number of decoded samples per channel still to be thrown away after a seek, because
+    /// they precede the seek target.
+    samples_to_skip: usize,
+}
+
+impl AudioPlayerInfo {
+    /// Drops buffered samples that precede the seek target, if any.
+    fn skip_leading_samples(&mut self) {
+        if self.samples_to_skip == 0 {
+            return
+        }
+        let samples = self.samples.as_mut().unwrap();
+        let count = match samples.iter().map(|channel| channel.len()).min() {
+            Some(count) => cmp::min(count, self.samples_to_skip),
+            None => return,
+        };
+        for channel in samples.iter_mut() {
+            channel.drain(0..count);
+        }
+        self.samples_to_skip -= count
+    }
 }
 
 pub struct DecodedFrame {
-    pub video_frame: Option<Box<DecodedVideoFrame + 'static>>,
+    /// The video frames to show, paired with the numbers of the tracks they belong to. The frame
+    /// of the primary video track comes first. Other video tracks only have an entry when a new
+    /// frame of theirs is due; otherwise, their previous frame should continue to be shown.
+    pub video_frames: Vec<(i64, Box<DecodedVideoFrame + 'static>)>,
     pub audio_samples: Option<Vec<Vec<f32>>>,
 }
 
-fn read_track_metadata_and_initialize_codecs(reader: &mut ContainerReader)
-                                             -> (Option<Box<VideoDecoder + 'static>>,
-                                                 Option<Box<AudioDecoder + 'static>>) {
-    let (mut video_codec, mut audio_codec) = (None, None);
-    for track_index in 0..reader.track_count() {
-        let track = reader.track_by_index(track_index);
-        match track.track_type() {
-            TrackType::Video => {
-                let video_track = track.as_video_track().unwrap();
-                if let Some(codec) = video_track.codec() {
-                    let headers = video_track.headers();
-                    video_codec = Some(RegisteredVideoDecoder::get(&codec).unwrap().new(
-                            &*headers,
-                            video_track.width() as i32,
-                            video_track.height() as i32).unwrap());
-                }
-            }
-            TrackType::Audio => {
-                let audio_track = track.as_audio_track().unwrap();
-                if let Some(codec) = audio_track.codec() {
-                    let headers = audio_track.headers();
-                    let info = RegisteredAudioDecoder::get(&codec).unwrap().new(
-                            &*headers,
-                            audio_track.sampling_rate(),
-                            audio_track.channels());
-                    audio_codec = Some(info.create_decoder());
-                }
-            }
-            _ => {}
+impl DecodedFrame {
+    /// Removes and returns the frame of the video track with the given number, if there is one.
+    pub fn take_video_frame(&mut self, track_number: i64)
+                            -> Option<Box<DecodedVideoFrame + 'static>> {
+        match self.video_frames.iter().position(|&(number, _)| number == track_number) {
+            Some(index) => Some(self.video_frames.remove(index).1),
+            None => None,
         }
     }
-    (video_codec, audio_codec)
+}
+
+fn create_video_codec(video_track: &VideoTrack) -> Result<Box<VideoDecoder + 'static>,()> {
+    let codec = match video_track.codec() {
+        Some(codec) => codec,
+        None => return Err(()),
+    };
+    let headers = video_track.headers();
+    let (width, height) = (video_track.coded_width() as i32, video_track.coded_height() as i32);
+
+    // Pick a decoder that can handle this particular stream, if we can tell.
+    let (profile, level) = match h264::profile_and_level(&*headers) {
+        Some((profile, level)) if &codec[..] == b"avc " => (Some(profile), Some(level)),
+        _ => (None, None),
+    };
+    let decoder = try!(RegisteredVideoDecoder::get_supporting(&codec,
+                                                              profile,
+                                                              level,
+                                                              width,
+                                                              height));
+    decoder.new(&*headers, width, height)
+}
+
+fn create_audio_codec(audio_track: &AudioTrack) -> Result<Box<AudioDecoder + 'static>,()> {
+    let codec = match audio_track.codec() {
+        Some(codec) => codec,
+        None => return Err(()),
+    };
+    let headers = audio_track.headers();
+    let info = try!(RegisteredAudioDecoder::get(&codec)).new(&*headers,
+                                                             audio_track.sampling_rate(),
+                                                             audio_track.channels());
+    Ok(info.create_decoder())
+}
+
+/// Returns the time of a frame, substituting the overridden time scale if there is one.
+fn frame_time(frame: &Frame, time_scale_override: Option<f64>) -> Timestamp {
+    let mut time = frame.time();
+    if let Some(ticks_per_second) = time_scale_override {
+        time.ticks_per_second = ticks_per_second
+    }
+    time
 }
 
 fn decode_video_frame(codec: &mut VideoDecoder,
                       frame: &Frame,
+                      time_scale_override: Option<f64>,
                       frames: &mut Vec<Box<DecodedVideoFrame + 'static>>) {
     let mut data = Vec::new();
     data.resize(frame.len() as usize, 0u8);
     frame.read(&mut data).unwrap();
 
-    let frame_presentation_time = frame.time() + frame.rendering_offset();
+    let frame_presentation_time = frame_time(frame, time_scale_override) +
+        frame.rendering_offset();
     if let Ok(image) = codec.decode_frame(&mut data, &frame_presentation_time) {
         frames.push(image)
     }
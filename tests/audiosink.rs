@@ -0,0 +1,46 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+
+use rust_media::audiosink::AudioRingBuffer;
+
+#[test]
+fn test_audio_ring_buffer_pause() {
+    let mut buffer = AudioRingBuffer::new();
+    buffer.push(&[0.25, 0.5, 0.75, 1.0]);
+
+    // While paused, the buffer emits silence and keeps its samples.
+    buffer.set_paused(true);
+    assert!(buffer.is_paused());
+    let mut output = [1.0; 4];
+    assert_eq!(buffer.pop(&mut output), 0);
+    assert_eq!(output, [0.0; 4]);
+    assert_eq!(buffer.len(), 4);
+
+    // Once resumed, playback picks up exactly where it left off.
+    buffer.set_paused(false);
+    let mut output = [0.0; 2];
+    assert_eq!(buffer.pop(&mut output), 2);
+    assert_eq!(output, [0.25, 0.5]);
+    assert_eq!(buffer.len(), 2);
+}
+
+#[test]
+fn test_audio_ring_buffer_underrun() {
+    let mut buffer = AudioRingBuffer::default();
+    assert!(buffer.is_empty());
+    buffer.push(&[0.5, 0.5]);
+    assert!(!buffer.is_empty());
+    let mut output = [1.0; 4];
+    assert_eq!(buffer.pop(&mut output), 2);
+    assert_eq!(output, [0.5, 0.5, 0.0, 0.0]);
+    assert_eq!(buffer.len(), 0);
+    assert!(buffer.is_empty());
+}
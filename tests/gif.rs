@@ -0,0 +1,101 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature="gif-encoder")]
+
+extern crate rust_media;
+extern crate time;
+
+use rust_media::containers::gif::{DisposalMode, ExtensionBlock, FileType};
+use rust_media::containers::gif::encoder::GifWriter;
+use std::env;
+use std::fs::{self, File};
+use std::process;
+use time::Duration;
+
+#[test]
+fn test_gif_encoder_round_trip() {
+    let (width, height) = (8, 8);
+    let red: Vec<u8> = (0..width * height).flat_map(|_| vec![255, 0, 0, 255]).collect();
+    let checkerboard: Vec<u8> = (0..width * height).flat_map(|i| {
+        if (i % width + i / width) % 2 == 0 {
+            vec![0, 255, 0, 255]
+        } else {
+            vec![0, 0, 0, 0]
+        }
+    }).collect();
+
+    // The GIF decoder can only read from files, so use one unique to this process.
+    let path = env::temp_dir().join(format!("rust-media-test-gif-encoder-{}.gif", process::id()));
+    {
+        let file = File::create(&path).unwrap();
+        let mut writer = GifWriter::new(file, width as u16, height as u16, 3).unwrap();
+        writer.write_frame(&red, Duration::milliseconds(100)).unwrap();
+        writer.write_frame(&checkerboard, Duration::milliseconds(250)).unwrap();
+        writer.finish().unwrap();
+    }
+
+    let mut file = FileType::new(Box::new(File::open(&path).unwrap())).unwrap();
+    file.slurp().unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(file.width(), width as i32);
+    assert_eq!(file.height(), height as i32);
+    let saved_images = file.saved_images();
+    assert_eq!(saved_images.len(), 2);
+
+    let mut delays = Vec::new();
+    let mut disposal_modes = Vec::new();
+    let mut transparent_colors = Vec::new();
+    let mut loop_extension = None;
+    for saved_image in saved_images.iter() {
+        for i in 0..saved_image.extension_block_count() {
+            match saved_image.extension_block(i) {
+                ExtensionBlock::Graphics(block) => {
+                    delays.push(block.delay_time());
+                    disposal_modes.push(block.disposal_mode());
+                    transparent_colors.push(block.transparent_color());
+                }
+                ExtensionBlock::Application(b"NETSCAPE2.0") => {
+                    if let ExtensionBlock::Continue(data) = saved_image.extension_block(i + 1) {
+                        loop_extension = Some(data.to_vec())
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    assert_eq!(delays, vec![10, 25]);
+    assert!(transparent_colors[0].is_none());
+
+    // The red frame must be cleared once it's been shown, or it would show through the transparent
+    // pixels of the checkerboard after it.
+    assert_eq!(disposal_modes[0], DisposalMode::Background);
+
+    // The loop count is stored little-endian after a sub-block ID of 1.
+    assert_eq!(loop_extension, Some(vec![1, 3, 0]));
+
+    // Every pixel of the first frame is red.
+    let color_map = saved_images[0].image_desc().color_map().unwrap();
+    for &index in saved_images[0].raster_bits().iter() {
+        let color = &color_map.colors()[index as usize];
+        assert_eq!((color.Red, color.Green, color.Blue), (255, 0, 0));
+    }
+
+    // The second frame alternates between green and transparent.
+    let transparent_color = transparent_colors[1].unwrap();
+    let color_map = saved_images[1].image_desc().color_map().unwrap();
+    for (i, &index) in saved_images[1].raster_bits().iter().enumerate() {
+        if (i % width + i / width) % 2 == 0 {
+            let color = &color_map.colors()[index as usize];
+            assert_eq!((color.Red, color.Green, color.Blue), (0, 255, 0));
+        } else {
+            assert_eq!(index as i32, transparent_color);
+        }
+    }
+}
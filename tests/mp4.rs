@@ -0,0 +1,27 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+
+use rust_media::containers::mp4::fixed_point_dimension_to_pixels;
+
+#[test]
+fn test_fixed_point_dimension_to_pixels() {
+    assert_eq!(fixed_point_dimension_to_pixels(1920.0), 1920);
+    // Anamorphic display sizes are rarely whole numbers of pixels.
+    assert_eq!(fixed_point_dimension_to_pixels(853.33), 853);
+    assert_eq!(fixed_point_dimension_to_pixels(853.5), 854);
+    assert_eq!(fixed_point_dimension_to_pixels(0.4), 0);
+    assert_eq!(fixed_point_dimension_to_pixels(0.0), 0);
+    assert_eq!(fixed_point_dimension_to_pixels(-16.0), 0);
+    // 16.16 fixed point goes up to 65535.99998.
+    assert_eq!(fixed_point_dimension_to_pixels(65535.4), 65535);
+    assert_eq!(fixed_point_dimension_to_pixels(65535.99), 65535);
+    assert_eq!(fixed_point_dimension_to_pixels(100000.0), 65535);
+}
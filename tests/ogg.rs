@@ -0,0 +1,121 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+
+use rust_media::container::{RegisteredContainerReader, Track, TrackType};
+use std::fs::File;
+
+/// Reads every frame of a track, checking that each belongs to it, and returns their times in
+/// ticks.
+fn frame_times(track: &Track) -> Vec<i64> {
+    let cluster = track.cluster(0).unwrap();
+    let mut times = Vec::new();
+    while let Ok(frame) = cluster.read_frame(times.len() as i32, track.number()) {
+        assert_eq!(frame.track_number(), track.number());
+        times.push(frame.time().ticks);
+    }
+    assert!(track.cluster(1).is_err());
+    times
+}
+
+#[test]
+fn test_ogg_vorbis_track() {
+    let file = Box::new(File::open("tests/samples/test.ogg").unwrap());
+    let reader = RegisteredContainerReader::get("audio/ogg").unwrap().new(file).unwrap();
+    assert_eq!(reader.track_count(), 1);
+
+    let track = reader.track_by_index(0);
+    assert_eq!(track.track_type(), TrackType::Audio);
+    assert_eq!(track.codec(), Some(b"vorb".to_vec()));
+    assert!(track.as_video_track().is_err());
+
+    let audio_track = track.as_audio_track().unwrap();
+    assert_eq!(audio_track.channels(), 1);
+    assert_eq!(audio_track.sampling_rate(), 44100.0);
+    let headers = audio_track.headers();
+    assert_eq!(&headers.vorbis_headers().unwrap().id()[1..7], b"vorbis");
+
+    // All the audio is on one page, so each packet has to be timed from its block size. The first
+    // packet decodes to nothing, the second overlaps a short block with a long one, and the rest
+    // are long blocks of 1024 samples each.
+    let times = frame_times(&*track);
+    assert_eq!(times.len(), 11);
+    assert_eq!(&times[0..4], &[0, 0, 128, 704]);
+    for pair in times[3..].windows(2) {
+        assert_eq!(pair[1] - pair[0], 1024);
+    }
+}
+
+#[test]
+fn test_ogg_theora_vorbis_tracks() {
+    let file = Box::new(File::open("tests/samples/test-theora-vorbis.ogg").unwrap());
+    let reader = RegisteredContainerReader::get("video/ogg").unwrap().new(file).unwrap();
+    assert_eq!(reader.track_count(), 2);
+
+    let video_track = reader.track_by_index(0);
+    assert_eq!(video_track.track_type(), TrackType::Video);
+    assert_eq!(video_track.codec(), Some(b"theo".to_vec()));
+    assert!(video_track.as_audio_track().is_err());
+    {
+        let video_track = video_track.as_video_track().unwrap();
+        assert_eq!(video_track.width(), 16);
+        assert_eq!(video_track.height(), 16);
+        assert_eq!(video_track.frame_rate(), 25.0);
+        assert_eq!(video_track.headers().theora_headers().unwrap().len(), 3);
+    }
+
+    let audio_track = reader.track_by_index(1);
+    assert_eq!(audio_track.track_type(), TrackType::Audio);
+    assert_eq!(audio_track.codec(), Some(b"vorb".to_vec()));
+    assert!(audio_track.as_video_track().is_err());
+    assert_ne!(audio_track.number(), video_track.number());
+    assert_eq!(reader.track_by_number(audio_track.number()).track_type(), TrackType::Audio);
+
+    // The pages of the two streams are interleaved, but each track only sees its own packets.
+    assert_eq!(frame_times(&*video_track), vec![0, 1, 2, 3, 4]);
+
+    // The audio is the stream from `test.ogg` split across several pages, which mustn't change the
+    // packet times.
+    let file = Box::new(File::open("tests/samples/test.ogg").unwrap());
+    let audio_only_reader = RegisteredContainerReader::get("audio/ogg").unwrap().new(file).unwrap();
+    assert_eq!(frame_times(&*audio_track), frame_times(&*audio_only_reader.track_by_index(0)));
+}
+
+#[test]
+fn test_ogg_theora_444_is_not_video() {
+    // Theora tracks are decoded as 4:2:0, so a 4:4:4 stream is left as an unknown track rather
+    // than shown in the wrong colors.
+    let file = Box::new(File::open("tests/samples/test-theora-444-vorbis.ogg").unwrap());
+    let reader = RegisteredContainerReader::get("video/ogg").unwrap().new(file).unwrap();
+    assert_eq!(reader.track_count(), 2);
+
+    let track = reader.track_by_index(0);
+    assert_eq!(track.track_type(), TrackType::Other);
+    assert_eq!(track.codec(), None);
+    assert!(track.as_video_track().is_err());
+    assert!(track.cluster(0).unwrap().read_frame(0, track.number()).is_err());
+
+    // The audio is unaffected.
+    let audio_track = reader.track_by_index(1);
+    assert_eq!(audio_track.track_type(), TrackType::Audio);
+    assert_eq!(frame_times(&*audio_track).len(), 11);
+}
+
+#[test]
+fn test_ogg_two_theora_tracks() {
+    let file = Box::new(File::open("tests/samples/test-theora-two-tracks.ogg").unwrap());
+    let reader = RegisteredContainerReader::get("video/ogg").unwrap().new(file).unwrap();
+    assert_eq!(reader.track_count(), 2);
+    let (first_track, second_track) = (reader.track_by_index(0), reader.track_by_index(1));
+    assert_eq!(first_track.as_video_track().unwrap().frame_rate(), 25.0);
+    assert_eq!(second_track.as_video_track().unwrap().frame_rate(), 12.5);
+    assert_eq!(frame_times(&*first_track), vec![0, 1, 2, 3, 4]);
+    assert_eq!(frame_times(&*second_track), vec![0, 1, 2]);
+}
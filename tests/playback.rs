@@ -0,0 +1,171 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+extern crate time;
+
+use rust_media::playback::Player;
+use rust_media::timing::Timestamp;
+use rust_media::videodecoder::DecodedVideoFrame;
+use std::f64;
+use std::fs::File;
+use time::Duration;
+
+fn open_audio_player<'a>() -> Player<'a> {
+    let file = Box::new(File::open("tests/samples/test.ogg").unwrap());
+    Player::new(file, "audio/ogg").unwrap()
+}
+
+fn open_gif_player<'a>() -> Player<'a> {
+    let file = Box::new(File::open("tests/samples/test.gif").unwrap());
+    Player::new(file, "image/gif").unwrap()
+}
+
+#[test]
+fn test_time_scale_override() {
+    let mut player = open_audio_player();
+    assert!(player.set_time_scale_override(0.0).is_err());
+    assert!(player.set_time_scale_override(-44100.0).is_err());
+    assert!(player.set_time_scale_override(f64::NAN).is_err());
+    assert!(player.set_time_scale_override(f64::INFINITY).is_err());
+
+    // The fourth Vorbis packet of the test file starts 704 samples in. Halving the time scale
+    // doubles its presentation time.
+    let mut overridden_player = open_audio_player();
+    overridden_player.set_time_scale_override(22050.0).unwrap();
+    for _ in 0..4 {
+        player.decode_frame().unwrap();
+        player.advance().unwrap();
+        overridden_player.decode_frame().unwrap();
+        overridden_player.advance().unwrap();
+    }
+    let time = player.next_frame_presentation_time().unwrap();
+    let overridden_time = overridden_player.next_frame_presentation_time().unwrap();
+    assert_eq!(time.ticks, 704);
+    assert_eq!(time.duration(), Timestamp { ticks: 704, ticks_per_second: 44100.0 }.duration());
+    assert_eq!(overridden_time.duration(),
+               Timestamp { ticks: 704, ticks_per_second: 22050.0 }.duration());
+}
+
+#[cfg(not(feature = "ffmpeg"))]
+#[test]
+fn test_player_skips_tracks_without_decoders() {
+    // Theora is only decoded through FFmpeg, so the audio track should play on its own.
+    let file = Box::new(File::open("tests/samples/test-theora-vorbis.ogg").unwrap());
+    let mut player = Player::new(file, "video/ogg").unwrap();
+    assert_eq!(player.video_track_number(), None);
+    assert!(player.audio_track_number().is_some());
+    player.decode_frame().unwrap();
+    assert!(player.advance().unwrap().video_frames.is_empty());
+}
+
+#[test]
+fn test_seek_audio() {
+    // Decode all 11 packets of the file to find out where each sample is.
+    let mut player = open_audio_player();
+    let mut samples = Vec::new();
+    for _ in 0..11 {
+        player.decode_frame().unwrap();
+        samples.extend_from_slice(&player.advance().unwrap().audio_samples.unwrap()[0]);
+    }
+    assert_eq!(samples.len(), 8896);
+
+    // Sample 1000 is in the fourth packet, which covers samples 704 to 1727, so 296 samples of it
+    // should be dropped.
+    let mut player = open_audio_player();
+    player.seek_audio(Duration::nanoseconds(1000 * 1_000_000_000 / 44100)).unwrap();
+    player.decode_frame().unwrap();
+    let seeked_samples = player.advance().unwrap().audio_samples.unwrap();
+    assert_eq!(seeked_samples[0].len(), 1024 - 296);
+    assert_eq!(&seeked_samples[0][..], &samples[1000..1728]);
+
+    // Playback carries on from there.
+    player.decode_frame().unwrap();
+    let next_samples = player.advance().unwrap().audio_samples.unwrap();
+    assert_eq!(&next_samples[0][..], &samples[1728..(1728 + next_samples[0].len())]);
+}
+
+#[test]
+fn test_seek_audio_to_end() {
+    // The last sample, 8895, is in the last packet.
+    let mut player = open_audio_player();
+    player.seek_audio(Duration::nanoseconds(8895 * 1_000_000_000 / 44100)).unwrap();
+    player.decode_frame().unwrap();
+    assert_eq!(player.advance().unwrap().audio_samples.unwrap()[0].len(), 1);
+
+    // Seeking to the end of the stream or anywhere past it fails.
+    let mut player = open_audio_player();
+    assert!(player.seek_audio(Duration::nanoseconds(8896 * 1_000_000_000 / 44100 + 1)).is_err());
+    assert!(player.seek_audio(Duration::seconds(10)).is_err());
+    assert!(player.seek_audio(Duration::days(365 * 300)).is_err());
+}
+
+#[test]
+fn test_decoded_video_size() {
+    let mut player = open_gif_player();
+    assert_eq!(player.decoded_video_size(), None);
+    player.decode_frame().unwrap();
+    assert_eq!(player.decoded_video_size(), Some((6, 4)));
+}
+
+#[test]
+fn test_add_video_output_errors() {
+    // The only track of a GIF is already being decoded.
+    let mut player = open_gif_player();
+    assert_eq!(player.video_track_numbers(), vec![0]);
+    assert!(player.add_video_output(0).is_err());
+    assert!(player.add_video_output(1).is_err());
+    assert_eq!(player.video_track_numbers(), vec![0]);
+
+    // Audio tracks can't be video outputs.
+    let mut player = open_audio_player();
+    let audio_track_number = player.audio_track_number().unwrap();
+    assert!(player.video_track_numbers().is_empty());
+    assert!(player.add_video_output(audio_track_number).is_err());
+    assert!(player.video_track_numbers().is_empty());
+}
+
+#[test]
+fn test_take_video_frame() {
+    let mut player = open_gif_player();
+    player.decode_frame().unwrap();
+    let mut frame = player.advance().unwrap();
+    assert!(frame.take_video_frame(1).is_none());
+    assert_eq!(frame.take_video_frame(0).unwrap().width(), 6);
+    assert!(frame.take_video_frame(0).is_none());
+    assert!(frame.video_frames.is_empty());
+}
+
+#[cfg(feature="ffmpeg")]
+#[test]
+fn test_secondary_video_track() {
+    use rust_media::container::{ContainerReader, Track};
+
+    // The first track runs at 25 frames per second, and the second at half that.
+    let file = Box::new(File::open("tests/samples/test-theora-two-tracks.ogg").unwrap());
+    let mut player = Player::new(file, "video/ogg").unwrap();
+    let track_numbers: Vec<i64> = (0..2).map(|index| {
+        player.reader.track_by_index(index).number() as i64
+    }).collect();
+    assert_eq!(player.video_track_numbers(), vec![track_numbers[0]]);
+    player.add_video_output(track_numbers[1]).unwrap();
+    assert!(player.add_video_output(track_numbers[1]).is_err());
+    assert_eq!(player.video_track_numbers(), track_numbers);
+
+    // The second track only has a new frame on every other frame of the first.
+    let mut secondary_frame_counts = Vec::new();
+    for _ in 0..3 {
+        player.decode_frame().unwrap();
+        let mut frame = player.advance().unwrap();
+        assert!(frame.take_video_frame(track_numbers[0]).is_some());
+        secondary_frame_counts.push(frame.take_video_frame(track_numbers[1]).into_iter().count());
+        assert!(frame.video_frames.is_empty());
+    }
+    assert_eq!(secondary_frame_counts, vec![1, 0, 1]);
+}
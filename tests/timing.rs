@@ -0,0 +1,36 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+extern crate time;
+
+use rust_media::timing::Timestamp;
+use time::Duration;
+
+#[test]
+fn test_timestamp_from_duration_at_coarse_time_scales() {
+    // GIF counts in hundredths of a second.
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(50), 100.0).ticks, 5);
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(1000), 100.0).ticks, 100);
+    // Anything shorter than a tick rounds toward zero.
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(5), 100.0).ticks, 0);
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(-5), 100.0).ticks, 0);
+
+    // Theora counts in frames.
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(1000), 30.0).ticks, 30);
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(100), 30.0).ticks, 3);
+    assert_eq!(Timestamp::from_duration(Duration::milliseconds(5), 30.0).ticks, 0);
+}
+
+#[test]
+fn test_timestamp_from_duration_round_trips() {
+    let timestamp = Timestamp::from_duration(Duration::milliseconds(1500), 30.0);
+    assert_eq!(timestamp.ticks_per_second, 30.0);
+    assert_eq!(timestamp.duration(), Duration::milliseconds(1500));
+}
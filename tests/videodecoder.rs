@@ -0,0 +1,29 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate rust_media;
+
+use rust_media::videodecoder::RegisteredVideoDecoder;
+
+#[test]
+fn test_video_decoder_supports() {
+    let vp8 = RegisteredVideoDecoder::get(b"VP80").unwrap();
+    assert!(vp8.supports(b"VP80", None, None, 640, 480));
+    assert!(vp8.supports(b"VP80", Some(3), None, 640, 480));
+    assert!(!vp8.supports(b"VP80", Some(4), None, 640, 480));
+    assert!(!vp8.supports(b"GIFf", None, None, 640, 480));
+}
+
+#[test]
+fn test_get_supporting_falls_back_to_registry() {
+    // Nothing claims an unknown VP8 profile, so the first registered VP8 decoder is used anyway.
+    let decoder = RegisteredVideoDecoder::get_supporting(b"VP80", Some(4), None, 640, 480);
+    assert_eq!(decoder.unwrap().id(), *b"VP80");
+    assert!(RegisteredVideoDecoder::get_supporting(b"XXXX", None, None, 640, 480).is_err());
+}
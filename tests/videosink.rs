@@ -0,0 +1,180 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate libc;
+extern crate rust_media;
+
+use libc::{c_int, c_uint};
+use rust_media::pixelformat::{Palette, PixelFormat, RgbColor};
+use rust_media::timing::Timestamp;
+use rust_media::videodecoder::{DecodedVideoFrame, DecodedVideoFrameLockGuard};
+use rust_media::videosink::{self, VideoSink, VideoSinkAdapter};
+
+fn is_i420(pixel_format: Option<PixelFormat>) -> bool {
+    match pixel_format {
+        Some(PixelFormat::I420) => true,
+        _ => false,
+    }
+}
+
+fn is_rgb24(pixel_format: Option<PixelFormat>) -> bool {
+    match pixel_format {
+        Some(PixelFormat::Rgb24) => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn test_negotiate_pixel_format() {
+    let candidates = [PixelFormat::Rgb24, PixelFormat::I420];
+
+    // Matching formats are passed through, even if they aren't the sink's first preference.
+    assert!(is_i420(videosink::negotiate_pixel_format(&PixelFormat::I420, &candidates)));
+    assert!(is_rgb24(videosink::negotiate_pixel_format(&PixelFormat::Rgb24, &candidates)));
+
+    // Otherwise, the cheapest conversion wins.
+    assert!(is_i420(videosink::negotiate_pixel_format(&PixelFormat::NV12, &candidates)));
+    let indexed = PixelFormat::Indexed(Palette::empty());
+    assert!(is_rgb24(videosink::negotiate_pixel_format(&indexed, &candidates)));
+
+    // Unsupported conversions yield nothing.
+    assert!(videosink::negotiate_pixel_format(&indexed, &[PixelFormat::I420]).is_none());
+}
+
+/// A frame with tightly packed planes, as if from a decoder.
+struct TestFrame {
+    pixel_format: PixelFormat<'static>,
+    planes: Vec<Vec<u8>>,
+    width: usize,
+    height: usize,
+}
+
+impl DecodedVideoFrame for TestFrame {
+    fn width(&self) -> c_uint {
+        self.width as c_uint
+    }
+
+    fn height(&self) -> c_uint {
+        self.height as c_uint
+    }
+
+    fn stride(&self, plane_index: usize) -> c_int {
+        self.pixel_format.plane_dimensions(plane_index, self.width, self.height).0 as c_int
+    }
+
+    fn presentation_time(&self) -> Timestamp {
+        Timestamp {
+            ticks: 0,
+            ticks_per_second: 1.0,
+        }
+    }
+
+    fn pixel_format<'a>(&'a self) -> PixelFormat<'a> {
+        self.pixel_format
+    }
+
+    fn lock<'a>(&'a self) -> Box<DecodedVideoFrameLockGuard + 'a> {
+        Box::new(TestFrameLockGuard {
+            planes: &self.planes,
+        }) as Box<DecodedVideoFrameLockGuard + 'a>
+    }
+}
+
+struct TestFrameLockGuard<'a> {
+    planes: &'a [Vec<u8>],
+}
+
+impl<'a> DecodedVideoFrameLockGuard for TestFrameLockGuard<'a> {
+    fn pixels<'b>(&'b self, plane_index: usize) -> &'b [u8] {
+        &self.planes[plane_index]
+    }
+}
+
+/// A sink that records the planes of each image presented to it.
+struct TestSink {
+    pixel_formats: Vec<PixelFormat<'static>>,
+    presented: Vec<Vec<Vec<u8>>>,
+}
+
+impl VideoSink for TestSink {
+    fn supported_pixel_formats(&self) -> Vec<PixelFormat<'static>> {
+        self.pixel_formats.clone()
+    }
+
+    fn present(&mut self,
+               pixels: &[&[u8]],
+               strides: &[usize],
+               pixel_format: PixelFormat,
+               width: usize,
+               height: usize)
+               -> Result<(),()> {
+        let mut planes = Vec::new();
+        for (plane, (pixels, &stride)) in pixels.iter().zip(strides.iter()).enumerate() {
+            let (row_length, rows) = pixel_format.plane_dimensions(plane, width, height);
+            let mut data = Vec::new();
+            for row in 0..rows {
+                data.extend_from_slice(&pixels[row * stride..row * stride + row_length]);
+            }
+            planes.push(data);
+        }
+        self.presented.push(planes);
+        Ok(())
+    }
+}
+
+fn present(pixel_formats: Vec<PixelFormat<'static>>, frame: TestFrame) -> Vec<Vec<u8>> {
+    let mut adapter = VideoSinkAdapter::new(TestSink {
+        pixel_formats: pixel_formats,
+        presented: Vec::new(),
+    });
+    adapter.present(&frame).unwrap();
+    adapter.into_sink().presented.pop().unwrap()
+}
+
+static PALETTE: [RgbColor; 2] = [
+    RgbColor { r: 255, g: 0, b: 0 },
+    RgbColor { r: 0, g: 0, b: 255 },
+];
+
+#[test]
+fn test_video_sink_adapter_converts_nv12_to_i420() {
+    let planes = present(vec![PixelFormat::I420], TestFrame {
+        pixel_format: PixelFormat::NV12,
+        planes: vec![(0..8).collect(), vec![1, 2, 3, 4]],
+        width: 4,
+        height: 2,
+    });
+    assert_eq!(planes, vec![(0..8).collect(), vec![1, 3], vec![2, 4]]);
+}
+
+#[test]
+fn test_video_sink_adapter_converts_indexed_to_rgb24() {
+    let planes = present(vec![PixelFormat::Rgb24], TestFrame {
+        pixel_format: PixelFormat::Indexed(Palette {
+            palette: &PALETTE,
+        }),
+        planes: vec![vec![0, 1, 1, 0]],
+        width: 2,
+        height: 2,
+    });
+    assert_eq!(planes, vec![vec![255, 0, 0, 0, 0, 255, 0, 0, 255, 255, 0, 0]]);
+}
+
+#[test]
+fn test_video_sink_adapter_converts_i420_to_rgb24() {
+    // Studio-swing white and black, with neutral chroma.
+    let planes = present(vec![PixelFormat::Rgb24], TestFrame {
+        pixel_format: PixelFormat::I420,
+        planes: vec![vec![235, 235, 16, 16, 235, 235, 16, 16], vec![128, 128], vec![128, 128]],
+        width: 4,
+        height: 2,
+    });
+    assert_eq!(planes, vec![vec![255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0,
+                                 255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]]);
+}
@@ -19,6 +19,19 @@ pub struct Timestamp {
 }
 
 impl Timestamp {
+    /// Converts a Rust duration to a timestamp with the given number of ticks per second,
+    /// rounding toward zero. This is the inverse of `.duration()`.
+    pub fn from_duration(duration: Duration, ticks_per_second: f64) -> Timestamp {
+        let seconds = match duration.num_nanoseconds() {
+            Some(nanoseconds) => nanoseconds as f64 / 1_000_000_000.0,
+            None => duration.num_milliseconds() as f64 / 1_000.0,
+        };
+        Timestamp {
+            ticks: (seconds * ticks_per_second) as i64,
+            ticks_per_second: ticks_per_second,
+        }
+    }
+
     pub fn duration(&self) -> Duration {
         Duration::nanoseconds(((self.ticks * 1_000_000_000) as f64 / self.ticks_per_second) as i64)
     }
@@ -29,10 +29,17 @@ pub trait VideoHeaders {
     fn h264_pict_headers<'a>(&'a self) -> Option<Vec<&'a [u8]>> {
         None
     }
+    fn theora_headers<'a>(&'a self) -> Option<Vec<&'a [u8]>> {
+        None
+    }
 }
 
 pub trait DecodedVideoFrame {
+    /// Returns the coded width of this frame in pixels. This may differ from the declared width of
+    /// the track it came from.
     fn width(&self) -> c_uint;
+    /// Returns the coded height of this frame in pixels. This may differ from the declared height
+    /// of the track it came from.
     fn height(&self) -> c_uint;
     fn stride(&self, plane_index: usize) -> c_int;
     fn presentation_time(&self) -> Timestamp;
@@ -55,6 +62,14 @@ pub struct RegisteredVideoDecoder {
     pub id: [u8; 4],
     pub constructor: extern "Rust" fn(headers: &VideoHeaders, width: i32, height: i32)
                                       -> Result<Box<VideoDecoder + 'static>,()>,
+    /// Reports whether this decoder can handle video with the given profile, level, and size on
+    /// this machine. The profile and level are `None` if unknown or if the codec has no such
+    /// notion.
+    pub supports: extern "Rust" fn(profile: Option<u32>,
+                                   level: Option<u32>,
+                                   width: i32,
+                                   height: i32)
+                                   -> bool,
 }
 
 impl RegisteredVideoDecoder {
@@ -67,11 +82,40 @@ impl RegisteredVideoDecoder {
         Err(())
     }
 
+    /// Returns the first registered decoder for the given codec that reports support for the
+    /// given profile, level, and size. If none does, falls back to the first registered decoder
+    /// for the codec, as `get()` does.
+    pub fn get_supporting(codec_id: &[u8],
+                          profile: Option<u32>,
+                          level: Option<u32>,
+                          width: i32,
+                          height: i32)
+                          -> Result<&'static RegisteredVideoDecoder,()> {
+        for decoder in VIDEO_DECODERS.iter() {
+            if decoder.supports(codec_id, profile, level, width, height) {
+                return Ok(decoder)
+            }
+        }
+        RegisteredVideoDecoder::get(codec_id)
+    }
+
     pub fn new(&self, headers: &VideoHeaders, width: i32, height: i32)
                -> Result<Box<VideoDecoder + 'static>,()> {
         (self.constructor)(headers, width, height)
     }
 
+    /// Returns true if this decoder can decode video in the given codec with the given profile,
+    /// level, and size on this machine.
+    pub fn supports(&self,
+                    codec_id: &[u8],
+                    profile: Option<u32>,
+                    level: Option<u32>,
+                    width: i32,
+                    height: i32)
+                    -> bool {
+        self.id == codec_id && (self.supports)(profile, level, width, height)
+    }
+
     pub fn id(&self) -> [u8; 4] {
         self.id
     }
@@ -80,7 +124,7 @@ impl RegisteredVideoDecoder {
 pub static VIDEO_DECODERS: [RegisteredVideoDecoder;
     2 +
     cfg!(target_os="macos") as usize +
-    cfg!(feature="ffmpeg") as usize
+    cfg!(feature="ffmpeg") as usize * 2
 ] = [
     vpx::VIDEO_DECODER,
     gif::VIDEO_DECODER,
@@ -88,4 +132,6 @@ pub static VIDEO_DECODERS: [RegisteredVideoDecoder;
     platform::macos::videotoolbox::VIDEO_DECODER,
     #[cfg(feature="ffmpeg")]
     libavcodec::VIDEO_DECODER,
+    #[cfg(feature="ffmpeg")]
+    libavcodec::THEORA_VIDEO_DECODER,
 ];
@@ -0,0 +1,134 @@
+// Copyright 2015 The Servo Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Abstract destinations for decoded video frames.
+
+use pixelformat::{ConvertPixelFormat, PixelFormat};
+use videodecoder::DecodedVideoFrame;
+
+/// Something that can display decoded video, such as a window or a texture.
+pub trait VideoSink {
+    /// Returns the pixel formats that this sink can display natively, in order of preference.
+    fn supported_pixel_formats(&self) -> Vec<PixelFormat<'static>>;
+
+    /// Displays an image. `pixels` and `strides` contain one entry per plane of `pixel_format`,
+    /// which is always one of the formats returned by `supported_pixel_formats()`. (If that format
+    /// is indexed, `pixel_format` carries the palette of this particular image.)
+    fn present(&mut self,
+               pixels: &[&[u8]],
+               strides: &[usize],
+               pixel_format: PixelFormat,
+               width: usize,
+               height: usize)
+               -> Result<(),()>;
+}
+
+/// Returns the pixel format among `candidates` that `pixel_format` can be converted to most
+/// cheaply, or `None` if it cannot be converted to any of them. Ties are broken in favor of the
+/// earlier candidate.
+pub fn negotiate_pixel_format(pixel_format: &PixelFormat, candidates: &[PixelFormat<'static>])
+                              -> Option<PixelFormat<'static>> {
+    let mut best: Option<(u32, PixelFormat<'static>)> = None;
+    for candidate in candidates.iter() {
+        let cost = match pixel_format.conversion_cost(candidate) {
+            Some(cost) => cost,
+            None => continue,
+        };
+        match best {
+            Some((best_cost, _)) if best_cost <= cost => {}
+            _ => best = Some((cost, *candidate)),
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Adapts a `VideoSink` to accept decoded frames in any pixel format, converting them on the CPU
+/// to whichever supported format is cheapest. Frames already in a supported format are passed
+/// through untouched.
+pub struct VideoSinkAdapter<S> {
+    /// The underlying sink.
+    sink: S,
+    /// Scratch buffers that converted frames are written into, one per plane.
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<S> VideoSinkAdapter<S> where S: VideoSink {
+    pub fn new(sink: S) -> VideoSinkAdapter<S> {
+        VideoSinkAdapter {
+            sink: sink,
+            buffers: Vec::new(),
+        }
+    }
+
+    pub fn sink<'a>(&'a self) -> &'a S {
+        &self.sink
+    }
+
+    pub fn sink_mut<'a>(&'a mut self) -> &'a mut S {
+        &mut self.sink
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    /// Converts the given frame to a format the sink supports, if necessary, and presents it.
+    pub fn present(&mut self, frame: &DecodedVideoFrame) -> Result<(),()> {
+        let (width, height) = (frame.width() as usize, frame.height() as usize);
+        let input_pixel_format = frame.pixel_format();
+        let output_pixel_format =
+            match negotiate_pixel_format(&input_pixel_format,
+                                         &self.sink.supported_pixel_formats()) {
+                Some(pixel_format) => pixel_format,
+                None => return Err(()),
+            };
+
+        // Gather up all the input pixels and strides.
+        let lock = frame.lock();
+        let (mut input_pixels, mut input_strides) = (Vec::new(), Vec::new());
+        for plane in 0..input_pixel_format.planes() {
+            input_pixels.push(lock.pixels(plane));
+            input_strides.push(frame.stride(plane) as usize);
+        }
+
+        if input_pixel_format.conversion_cost(&output_pixel_format) == Some(0) {
+            return self.sink.present(&input_pixels,
+                                     &input_strides,
+                                     input_pixel_format,
+                                     width,
+                                     height)
+        }
+
+        // Make room for the converted image.
+        let plane_count = output_pixel_format.planes();
+        self.buffers.resize(plane_count, Vec::new());
+        let mut output_strides = Vec::new();
+        for plane in 0..plane_count {
+            let (stride, rows) = output_pixel_format.plane_dimensions(plane, width, height);
+            self.buffers[plane].resize(stride * rows, 0);
+            output_strides.push(stride);
+        }
+
+        // Perform pixel format conversion.
+        {
+            let mut output_pixels: Vec<&mut [u8]> =
+                self.buffers.iter_mut().map(|buffer| &mut buffer[..]).collect();
+            try!(input_pixel_format.convert(&output_pixel_format,
+                                            output_pixels.as_mut_slice(),
+                                            output_strides.as_slice(),
+                                            input_pixels.as_slice(),
+                                            input_strides.as_slice(),
+                                            width,
+                                            height));
+        }
+
+        let output_pixels: Vec<&[u8]> = self.buffers.iter().map(|buffer| &buffer[..]).collect();
+        self.sink.present(&output_pixels, &output_strides, output_pixel_format, width, height)
+    }
+}